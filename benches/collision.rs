@@ -0,0 +1,198 @@
+//! Times a tick's worth of bullet collision checks in a crowded scene, with and
+//! without the spatial index, against the time one frame at 60 FPS has to spare.
+//!
+//! Run with `cargo bench --bench collision`.
+
+use glhf::{
+    config::ui::UI,
+    game::{
+        bullets::{Bullet, Kind},
+        components::Faction,
+        enemies::Enemy,
+        health::HealthPoints,
+        player::Player,
+        simulation::Simulation,
+        systems::{self, BulletIndex, Impacts},
+        world::{Entity, World},
+    },
+    physics::{
+        motion::{Dimensions, Kinematics, Position},
+        units::Pixels,
+    },
+};
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+
+const ENEMIES: usize = 50;
+const ROUNDS: u32 = 100;
+
+/// One frame at 60 FPS.
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+/// Sturdy enemies laid out in a grid, the same every run.
+fn enemies() -> impl Iterator<Item = Enemy> {
+    (0..ENEMIES).map(|index| {
+        let x = (index % 10) as f32 * 58.0 + 10.0;
+        let y = (index / 10) as f32 * 80.0 + 20.0;
+
+        Enemy::new(
+            Position::new(Pixels(x), Pixels(y)),
+            Dimensions::new(Pixels(48.0), Pixels(48.0)),
+            HealthPoints::new(u32::MAX),
+            vec![],
+        )
+    })
+}
+
+/// Stationary bullets strewn across the screen, the same every run.
+fn bullets(enemy_bullets: usize, player_bullets: usize) -> impl Iterator<Item = Bullet> {
+    (0..enemy_bullets + player_bullets).map(move |index| {
+        let faction = if index < enemy_bullets { Faction::Enemy } else { Faction::Player };
+        let x = (index * 37 % 580) as f32;
+        let y = (index * 53 % 780) as f32;
+
+        Bullet::new(faction, Kind::Basic, Position::new(Pixels(x), Pixels(y)))
+            .with_kinematics(Kinematics::stationary())
+    })
+}
+
+/// A screen full of bullets and enemies around the player.
+struct Scene {
+    world: World,
+    player: Entity,
+    index: BulletIndex,
+    impacts: Impacts,
+}
+
+fn crowded(enemy_bullets: usize, player_bullets: usize) -> Scene {
+    let ui = UI::default();
+    let mut world = World::new();
+    let player = world.spawn(Player::new(&ui));
+
+    for enemy in enemies() {
+        world.spawn(enemy);
+    }
+
+    for bullet in bullets(enemy_bullets, player_bullets) {
+        world.spawn(bullet);
+    }
+
+    Scene {
+        world,
+        player,
+        index: BulletIndex::new(ui.hitbox_rect(), 64.0),
+        impacts: Impacts::default(),
+    }
+}
+
+/// The same scene as a whole game, to time full ticks of.
+fn crowded_simulation(enemy_bullets: usize, player_bullets: usize) -> Simulation {
+    let mut simulation = Simulation::new(UI::default(), 0);
+
+    for enemy in enemies() {
+        simulation.add_enemy(enemy);
+    }
+
+    for bullet in bullets(enemy_bullets, player_bullets) {
+        simulation.add_bullet(bullet);
+    }
+
+    simulation
+}
+
+/// Every bullet against the player and every enemy without the index, counting the
+/// bullets that hit something.
+fn pairwise(scene: &Scene) -> usize {
+    let world = &scene.world;
+    let player = world.hitbox(scene.player).unwrap();
+
+    world
+        .bullets()
+        .filter(|&bullet| {
+            let hitbox = world.hitbox(bullet).unwrap();
+
+            match world.factions.get(bullet) {
+                Some(Faction::Enemy) => hitbox.overlaps(&player),
+                _ => world.enemies().any(|enemy| hitbox.overlaps(&world.hitbox(enemy).unwrap())),
+            }
+        })
+        .count()
+}
+
+fn indexed(scene: &mut Scene) -> usize {
+    scene.impacts.clear();
+
+    systems::index_bullets(&scene.world, &mut scene.index);
+    systems::player_collisions(&scene.world, &mut scene.impacts);
+    systems::enemy_collisions(&mut scene.world, &mut scene.index, &mut scene.impacts);
+
+    scene.impacts.spent.len()
+}
+
+/// The average time `f` takes over `ROUNDS` calls.
+fn time<F: FnMut() -> usize>(mut f: F) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+
+    start.elapsed() / ROUNDS
+}
+
+fn report(name: &str, took: Duration) {
+    println!(
+        "  {:<10} {:>10.3} ms  ({:.1}% of a frame)",
+        name,
+        took.as_secs_f64() * 1000.0,
+        took.as_secs_f64() / FRAME_BUDGET.as_secs_f64() * 100.0,
+    );
+}
+
+/// Time both ways of finding hits, and whole ticks, in a scene with this many bullets
+/// of each owner, returning the slowest of the indexed checks and the tick.
+fn scene(name: &str, enemy_bullets: usize, player_bullets: usize) -> Duration {
+    let mut scene = crowded(enemy_bullets, player_bullets);
+
+    assert_eq!(pairwise(&scene), indexed(&mut scene), "the index should find the same hits");
+
+    println!(
+        "{}: {} enemy and {} player bullets against {} enemies",
+        name,
+        enemy_bullets,
+        player_bullets,
+        scene.world.enemies().count(),
+    );
+
+    let pairwise = time(|| pairwise(&scene));
+    report("pairwise", pairwise);
+
+    let indexed = time(|| indexed(&mut scene));
+    report("indexed", indexed);
+
+    let mut simulation = crowded_simulation(enemy_bullets, player_bullets);
+    let tick = time(|| {
+        simulation.tick(&[]);
+        simulation.world().len()
+    });
+    report("full tick", tick);
+
+    indexed.max(tick)
+}
+
+fn main() {
+    let slowest = [
+        scene("bullet hell", 5_000, 200),
+        scene("crossfire", 2_500, 2_500),
+        scene("all guns", 0, 5_000),
+    ]
+    .into_iter()
+    .max()
+    .unwrap();
+
+    assert!(slowest < FRAME_BUDGET, "collisions or a tick took longer than a frame");
+}
@@ -1,4 +1,4 @@
-use ggez::graphics;
+use crate::physics::collision;
 
 
 /// Configuration options for the game UI.
@@ -10,13 +10,13 @@ pub struct UI {
 impl UI {
     pub fn new(width: f32, height: f32) -> Self {
         UI {
-            width: width,
-            height: height,
+            width,
+            height,
         }
     }
     
-    pub fn hitbox_rect(&self) -> graphics::Rect {
-        graphics::Rect::new(0.0, 0.0, self.width, self.height)
+    pub fn hitbox_rect(&self) -> collision::Rect {
+        collision::Rect::new(0.0, 0.0, self.width, self.height)
     }
 }
 
@@ -0,0 +1,342 @@
+//! Enemy archetypes: the stats, looks and weapons of each type of enemy, kept in
+//! data rather than in code.
+//!
+//! The bundled archetypes live in `resources/archetypes.ron`, a map from name to
+//! archetype, and stages can add their own. For example:
+//!
+//! ```ron
+//! {
+//!     "popcorn": Archetype(
+//!         size: (24, 24),
+//!         colour: (255, 160, 0),
+//!         health: 20,
+//!         hitbox: Circle(centre: (12, 12), radius: 8),
+//!         score: 100,
+//!         drops: [Drop(item: "power", chance: 0.25)],
+//!         emitters: [
+//!             Emitter(pattern: Aimed(count: 1, arc: 0), speed: 0.3, interval: 1500),
+//!         ],
+//!     ),
+//! }
+//! ```
+//!
+//! Sizes and positions are in pixels, angles in degrees clockwise from the positive
+//! x axis, speeds in pixels per millisecond and intervals in milliseconds.
+
+use crate::{
+    game::{
+        boss,
+        bullets,
+        components::{Colour, Faction},
+        enemies::{Drop, Enemy},
+        health::HealthPoints,
+        patterns::{self, Aim, Emitter},
+        script::Script,
+        stage::{StageError, Step},
+    },
+    physics::{
+        collision,
+        motion::{
+            Dimensions,
+            Offset,
+            Position,
+        },
+        units::{
+            Degrees,
+            Pixels,
+            PixelsPerMs,
+            Radians,
+            RadiansPerMs,
+        },
+    },
+};
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+};
+
+use chrono::Duration;
+
+use serde::Deserialize;
+
+
+/// The archetypes that ship with the game.
+const BUNDLED: &str = include_str!("../../resources/archetypes.ron");
+
+/// An `(x, y)` pair in pixels.
+pub type Point = (f32, f32);
+
+/// Everything that sets one type of enemy apart from another.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Archetype {
+    /// The width and height the enemy is drawn at.
+    pub size: Point,
+    #[serde(default = "magenta")]
+    pub colour: Colour,
+    /// Every archetype but a boss needs some; bosses take their health from their
+    /// phases instead.
+    #[serde(default)]
+    pub health: u32,
+    /// The part of the enemy bullets can hit, relative to its top left corner, if
+    /// not all of it.
+    #[serde(default)]
+    pub hitbox: Option<ShapeSpec>,
+    /// How many points shooting the enemy down is worth.
+    #[serde(default)]
+    pub score: u64,
+    #[serde(default)]
+    pub drops: Vec<Drop>,
+    #[serde(default)]
+    pub emitters: Vec<EmitterSpec>,
+    #[serde(default)]
+    pub script: Option<Script>,
+    /// Where bullets come from relative to the enemy, if not the middle of its bottom edge.
+    #[serde(default)]
+    pub muzzle: Option<Point>,
+    /// A boss's phases, each taking over its health, weapons and movement in turn.
+    #[serde(default)]
+    pub phases: Vec<PhaseSpec>,
+}
+
+/// One phase of a boss fight.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename = "Phase")]
+pub struct PhaseSpec {
+    pub name: String,
+    pub health: u32,
+    /// Milliseconds before the boss moves on to its next phase regardless.
+    #[serde(default)]
+    pub duration: Option<u32>,
+    #[serde(default)]
+    pub emitters: Vec<EmitterSpec>,
+    #[serde(default)]
+    pub script: Option<Script>,
+    /// Where to move during the phase, starting from wherever the boss is when it begins.
+    #[serde(default)]
+    pub path: Vec<Step>,
+}
+
+/// A bullet pattern an enemy fires automatically.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename = "Emitter")]
+pub struct EmitterSpec {
+    pub pattern: PatternSpec,
+    pub speed: f32,
+    /// Milliseconds between volleys.
+    pub interval: u32,
+    #[serde(default = "straight_down")]
+    pub heading: f32,
+    /// Degrees per second.
+    #[serde(default)]
+    pub rotation_rate: f32,
+    /// Degrees per second.
+    #[serde(default)]
+    pub curve: f32,
+    #[serde(default)]
+    pub aim: Aim,
+    #[serde(default)]
+    pub kind: bullets::Kind,
+}
+
+/// The same as `patterns::Pattern`, but with angles in degrees.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename = "Pattern")]
+pub enum PatternSpec {
+    Spread { count: u32, arc: f32 },
+    Ring { count: u32 },
+    Spiral { arms: u32, step: f32 },
+    Aimed { count: u32, arc: f32 },
+    Scatter { count: u32, arc: f32, speed_variance: f32 },
+}
+
+/// The same as `collision::Shape`, but with angles in degrees and rotated
+/// rectangles given by their full size.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename = "Shape")]
+pub enum ShapeSpec {
+    Rect { x: f32, y: f32, w: f32, h: f32 },
+    Circle { centre: Point, radius: f32 },
+    Capsule { from: Point, to: Point, radius: f32 },
+    RotatedRect { centre: Point, size: Point, angle: f32 },
+}
+
+/// Archetypes by name.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Registry {
+    archetypes: HashMap<String, Archetype>,
+}
+
+impl Archetype {
+    /// Make sure the archetype called `name` would spawn alive: anything but a boss
+    /// needs some health of its own.
+    pub fn check(&self, name: &str) -> Result<(), StageError> {
+        if self.health == 0 && self.phases.is_empty() {
+            return Err(StageError::NoHealth { archetype: name.to_string() });
+        }
+
+        Ok(())
+    }
+
+    /// A new enemy of this archetype with its top left corner at `position`.
+    pub fn spawn(&self, position: Position<Pixels>) -> Enemy {
+        let (width, height) = self.size;
+        let emitters = self.emitters.iter().map(EmitterSpec::emitter).collect();
+
+        let mut enemy = Enemy::new(
+            position,
+            Dimensions::new(Pixels(width), Pixels(height)),
+            HealthPoints::new(self.health),
+            emitters,
+        )
+        .with_colour(self.colour)
+        .with_score(self.score)
+        .with_drops(self.drops.clone());
+
+        if let Some(hitbox) = self.hitbox {
+            enemy = enemy.with_hitbox(hitbox.shape());
+        }
+
+        if let Some(script) = self.script.as_ref() {
+            enemy = enemy.with_script(script);
+        }
+
+        if let Some((x, y)) = self.muzzle {
+            enemy = enemy.with_muzzle(Offset::new(Pixels(x), Pixels(y)));
+        }
+
+        if !self.phases.is_empty() {
+            enemy = enemy.with_phases(self.phases.iter().map(PhaseSpec::phase).collect());
+        }
+
+        enemy
+    }
+}
+
+impl PhaseSpec {
+    pub fn phase(&self) -> boss::Phase {
+        boss::Phase {
+            name: self.name.clone(),
+            health: self.health,
+            duration: self.duration.map(|duration| Duration::milliseconds(duration as i64)),
+            emitters: self.emitters.iter().map(EmitterSpec::emitter).collect(),
+            script: self.script.clone(),
+            path: self.path.clone(),
+        }
+    }
+}
+
+impl EmitterSpec {
+    pub fn emitter(&self) -> Emitter {
+        Emitter::new(
+            Faction::Enemy,
+            self.kind,
+            self.pattern.pattern(),
+            PixelsPerMs(self.speed),
+            Duration::milliseconds(self.interval as i64),
+        )
+        .with_heading(Degrees(self.heading))
+        .with_rotation_rate(degrees_per_second(self.rotation_rate))
+        .with_curve(degrees_per_second(self.curve))
+        .with_aim(self.aim)
+    }
+}
+
+impl PatternSpec {
+    pub fn pattern(&self) -> patterns::Pattern {
+        use patterns::Pattern;
+
+        let radians = |degrees: f32| Radians::from(Degrees(degrees));
+
+        match *self {
+            PatternSpec::Spread { count, arc } => Pattern::Spread { count, arc: radians(arc) },
+            PatternSpec::Ring { count } => Pattern::Ring { count },
+            PatternSpec::Spiral { arms, step } => Pattern::Spiral { arms, step: radians(step) },
+            PatternSpec::Aimed { count, arc } => Pattern::Aimed { count, arc: radians(arc) },
+            PatternSpec::Scatter { count, arc, speed_variance } => Pattern::Scatter {
+                count,
+                arc: radians(arc),
+                speed_variance: PixelsPerMs(speed_variance),
+            },
+        }
+    }
+}
+
+impl ShapeSpec {
+    pub fn shape(&self) -> collision::Shape {
+        use collision::Shape;
+
+        match *self {
+            ShapeSpec::Rect { x, y, w, h } => Shape::Rect(collision::Rect::new(x, y, w, h)),
+            ShapeSpec::Circle { centre, radius } => Shape::Circle { centre, radius },
+            ShapeSpec::Capsule { from, to, radius } => Shape::Capsule { from, to, radius },
+            ShapeSpec::RotatedRect { centre, size: (w, h), angle } => Shape::RotatedRect {
+                centre,
+                half_extents: (w / 2.0, h / 2.0),
+                angle: Radians::from(Degrees(angle)),
+            },
+        }
+    }
+}
+
+impl Registry {
+    /// The archetypes that ship with the game.
+    pub fn bundled() -> Self {
+        Registry::parse(BUNDLED).expect("the bundled archetypes are valid")
+    }
+
+    pub fn parse(source: &str) -> Result<Self, StageError> {
+        let registry: Registry = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_str(source)
+            .map_err(|err| StageError::Syntax {
+                line: err.position.line,
+                column: err.position.col,
+                message: err.code.to_string(),
+            })?;
+
+        for (name, archetype) in registry.archetypes.iter() {
+            archetype.check(name)?;
+        }
+
+        Ok(registry)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, StageError> {
+        Registry::parse(&fs::read_to_string(path).map_err(StageError::Io)?)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Archetype> {
+        self.archetypes.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.archetypes.contains_key(name)
+    }
+
+    /// Add `archetypes`, replacing any existing ones of the same name.
+    pub fn extend<I: IntoIterator<Item = (String, Archetype)>>(&mut self, archetypes: I) {
+        self.archetypes.extend(archetypes);
+    }
+
+    /// Every archetype's name, in alphabetical order.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.archetypes.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+fn degrees_per_second(degrees: f32) -> RadiansPerMs {
+    RadiansPerMs(Radians::from(Degrees(degrees)).value() / 1000.0)
+}
+
+fn straight_down() -> f32 {
+    90.0
+}
+
+fn magenta() -> Colour {
+    (255, 0, 255)
+}
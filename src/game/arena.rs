@@ -0,0 +1,178 @@
+/// A reference to a value in an `Arena` that stays valid for as long as the value
+/// does, however much else is added or removed around it.
+///
+/// Once the value is removed its handle goes stale: the slot it pointed at may be
+/// reused, but the handle won't find the new value there.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+/// Storage for values that come and go often, such as bullets, where each value can
+/// be looked up, and removed, by its `Handle`.
+///
+/// Values are iterated over in the order of the slots they occupy. Removed values
+/// leave their slot free for the next value inserted, most recently freed first,
+/// so the same insertions and removals always give the same order. An arena made
+/// `with_capacity` works as a pool: nothing is allocated to insert or remove values
+/// until it holds more than that many at once.
+#[derive(Clone, Debug)]
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+impl Handle {
+    /// The slot the handle points at, e.g. to store in a `spatial::Grid`.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Arena {
+            slots: vec![],
+            free: vec![],
+            len: 0,
+        }
+    }
+
+    /// An arena with room for `capacity` values before it needs to allocate more.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Arena {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// How many values the arena can hold before it needs to allocate more.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle {
+        self.len += 1;
+
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.value = Some(value);
+
+                Handle { index, generation: slot.generation }
+            },
+            None => {
+                self.slots.push(Slot { generation: 0, value: Some(value) });
+
+                Handle { index: self.slots.len() as u32 - 1, generation: 0 }
+            },
+        }
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.slots
+            .get(handle.index())
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.slots
+            .get_mut(handle.index())
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// The handle of whatever is in slot `index`, if anything.
+    pub fn handle_at(&self, index: usize) -> Option<Handle> {
+        self.slots
+            .get(index)
+            .filter(|slot| slot.value.is_some())
+            .map(|slot| Handle { index: index as u32, generation: slot.generation })
+    }
+
+    /// Take the value out of the arena, unless it has already gone.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let slot = self.slots.get_mut(handle.index()).filter(|slot| slot.generation == handle.generation)?;
+        let value = slot.value.take()?;
+
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.len -= 1;
+
+        Some(value)
+    }
+
+    /// Remove every value for which `keep` returns `false`, in one pass.
+    pub fn retain<F: FnMut(Handle, &mut T) -> bool>(&mut self, mut keep: F) {
+        for index in 0..self.slots.len() {
+            let slot = &mut self.slots[index];
+            let handle = Handle { index: index as u32, generation: slot.generation };
+
+            if let Some(value) = slot.value.as_mut() {
+                if !keep(handle, value) {
+                    self.remove(handle);
+                }
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let handle = Handle { index: index as u32, generation: slot.generation };
+
+            slot.value.as_ref().map(|value| (handle, value))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            let handle = Handle { index: index as u32, generation: slot.generation };
+
+            slot.value.as_mut().map(|value| (handle, value))
+        })
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena::new()
+    }
+}
+
+impl<T> Extend<T> for Arena<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.insert(value);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::Duration;
+
+
+/// How much a bomb hurts every enemy on screen.
+pub const DAMAGE: u32 = 100;
+/// How much a bomb hurts a boss, which it never finishes a phase of.
+pub const BOSS_DAMAGE: u32 = 25;
+/// How long a bomb keeps the player safe from bullets, and how long it goes on
+/// for before another can be set off.
+pub const INVULNERABILITY: Duration = Duration::milliseconds(2000);
+
+/// The player's stock of bombs, which clear the screen of enemy bullets and hurt
+/// every enemy on it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bombs {
+    stock: u32,
+    maximum: u32,
+    /// How much longer the last bomb goes on for.
+    active_for: Duration,
+}
+
+impl Bombs {
+    /// `stock` bombs, with room for up to `maximum`.
+    pub fn new(stock: u32, maximum: u32) -> Self {
+        Bombs {
+            stock: stock.min(maximum),
+            maximum,
+            active_for: Duration::zero(),
+        }
+    }
+
+    pub fn stock(&self) -> u32 {
+        self.stock
+    }
+
+    pub fn maximum(&self) -> u32 {
+        self.maximum
+    }
+
+    /// Whether a bomb is still going off.
+    pub fn is_active(&self) -> bool {
+        self.active_for > Duration::zero()
+    }
+
+    /// Use up a bomb, returning whether there was one to use. There isn't while
+    /// the last one is still going off.
+    pub fn take(&mut self) -> bool {
+        if self.stock == 0 || self.is_active() {
+            return false;
+        }
+
+        self.stock -= 1;
+        self.active_for = INVULNERABILITY;
+        true
+    }
+
+    /// Let `time` pass for the bomb going off, if there is one.
+    pub fn update(&mut self, time: Duration) {
+        self.active_for = (self.active_for - time).max(Duration::zero());
+    }
+
+    /// Add `count` bombs, as many as there is room for, returning the new stock.
+    pub fn replenish(&mut self, count: u32) -> u32 {
+        self.stock = (self.stock + count).min(self.maximum);
+        self.stock
+    }
+}
@@ -0,0 +1,87 @@
+use crate::game::{
+    patterns::Emitter,
+    script::Script,
+    stage::Step,
+};
+
+use std::rc::Rc;
+
+use chrono::Duration;
+
+
+/// One stage of a boss fight, with its own health bar, movement and weapons.
+#[derive(Clone, Debug)]
+pub struct Phase {
+    pub name: String,
+    pub health: u32,
+    /// How long the phase lasts before the boss moves on regardless, or `None` to
+    /// last until its health runs out.
+    pub duration: Option<Duration>,
+    pub emitters: Vec<Emitter>,
+    pub script: Option<Script>,
+    /// Where to move during the phase, starting from wherever the last one left off.
+    pub path: Vec<Step>,
+}
+
+/// Tracks which phase of its fight a boss is on and how long it has been on it.
+#[derive(Clone, Debug)]
+pub struct Phases {
+    phases: Rc<Vec<Phase>>,
+    current: usize,
+    elapsed: Duration,
+}
+
+impl Phases {
+    /// Start a fight at the first of `phases`, of which there must be at least one.
+    pub fn new(phases: Vec<Phase>) -> Self {
+        assert!(!phases.is_empty(), "a boss needs at least one phase");
+
+        Phases {
+            phases: Rc::new(phases),
+            current: 0,
+            elapsed: Duration::zero(),
+        }
+    }
+
+    pub fn current(&self) -> &Phase {
+        &self.phases[self.current]
+    }
+
+    /// Which phase the fight is on, counting from zero.
+    pub fn index(&self) -> usize {
+        self.current
+    }
+
+    pub fn count(&self) -> usize {
+        self.phases.len()
+    }
+
+    /// How many phases are left after this one.
+    pub fn remaining(&self) -> usize {
+        self.count() - self.current - 1
+    }
+
+    /// How long is left before the current phase is forced to end, if it has a time limit.
+    pub fn time_left(&self) -> Option<Duration> {
+        self.current().duration.map(|duration| (duration - self.elapsed).max(Duration::zero()))
+    }
+
+    /// Let `time` pass, returning whether the current phase has run out of time.
+    pub fn advance(&mut self, time: Duration) -> bool {
+        self.elapsed += time;
+
+        self.time_left() == Some(Duration::zero())
+    }
+
+    /// Move on to the next phase, if there is one.
+    pub fn next_phase(&mut self) -> Option<&Phase> {
+        if self.current + 1 >= self.phases.len() {
+            return None;
+        }
+
+        self.current += 1;
+        self.elapsed = Duration::zero();
+
+        Some(self.current())
+    }
+}
@@ -1,120 +1,367 @@
 use crate::{
-    game::health::HealthPoints,
+    game::{
+        components::{Colour, Faction, Hitbox, Layer, Renderable, Transform, Velocity},
+        health::HealthPoints,
+        script,
+        world::{Bundle, Entity, World},
+    },
+    physics::collision,
     physics::motion::*,
     physics::units,
 };
 
 use chrono::Duration;
-use ggez::{
-    graphics,
-    Context,
-    GameResult,
-};
 
+use serde::Deserialize;
+
+use std::f32::consts::{PI, TAU};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum Owner {
-    Player,
-    Enemy,
-}
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The sorts of bullet there are. In stage files an emitter's bullets are `Basic`
+/// unless it says otherwise, e.g. `kind: Homing`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
 pub enum Kind {
+    #[default]
     Basic,
+    /// Turns towards its target as it flies: the nearest enemy for the player's
+    /// bullets, and the player for enemies'.
+    Homing,
+    /// Goes straight through the first few enemies it hits.
+    Piercing,
+    /// Bursts on the first enemy it hits, catching any others close by in the blast.
+    Burst,
+}
+
+/// What happens to a bullet when it hits enemies. Bullets that hit the player always
+/// stop there.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HitPolicy {
+    /// Hit only the first enemy touched, then disappear.
+    First,
+    /// Carry on through up to this many different enemies, hitting each one once.
+    Pierce(u32),
+    /// Burst on the first enemy touched, hitting every enemy within `radius` pixels
+    /// of the middle of the bullet.
+    Area { radius: f32 },
+}
+
+/// How a homing bullet steers. Its heading is the direction of its velocity, which
+/// turns towards the target by at most `turn_rate`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Homing {
+    pub turn_rate: units::RadiansPerMs,
+    /// How much longer the bullet flies before fizzling out.
+    lifetime: Duration,
+    /// The enemy the bullet is chasing, if it has picked one.
+    lock: Option<Entity>,
 }
 
+/// What makes a bullet a bullet: how hard it hits, what it does on hitting
+/// something and the script it runs.
 #[derive(Clone)]
-pub struct Bullet {
-    owner: Owner,
+pub struct Projectile {
     kind: Kind,
-    position: Position<units::Pixels>,
-    dimensions: Dimensions<units::Pixels>,
+    damage: HealthPoints,
+    hit_policy: HitPolicy,
+    /// The enemies a piercing bullet has already been through.
+    pierced: Vec<Entity>,
+    script: Option<Box<script::Runner>>,
+    homing: Option<Homing>,
+}
+
+/// A bullet's components, ready to spawn.
+#[derive(Clone)]
+pub struct Bullet {
+    pub transform: Transform,
+    pub velocity: Velocity,
+    /// The part of the bullet that hits things, relative to its position.
+    pub hitbox: Hitbox,
+    pub faction: Faction,
+    pub projectile: Projectile,
+}
+
+impl Projectile {
+    pub fn new(faction: Faction, kind: Kind) -> Self {
+        Projectile {
+            kind,
+            damage: kind.damage(faction, 0),
+            hit_policy: kind.hit_policy(),
+            pierced: vec![],
+            script: None,
+            homing: kind.homing(faction),
+        }
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    pub fn damage(&self) -> HealthPoints {
+        self.damage
+    }
+
+    pub fn hit_policy(&self) -> HitPolicy {
+        self.hit_policy
+    }
+
+    pub fn homing(&self) -> Option<&Homing> {
+        self.homing.as_ref()
+    }
+
+    pub fn homing_mut(&mut self) -> Option<&mut Homing> {
+        self.homing.as_mut()
+    }
+
+    /// Whether the bullet has already been through `enemy`.
+    pub fn has_pierced(&self, enemy: Entity) -> bool {
+        self.pierced.contains(&enemy)
+    }
+
+    /// Remember that the bullet has been through `enemy`, returning whether it has
+    /// now been through as many enemies as it can.
+    pub fn pierce(&mut self, enemy: Entity) -> bool {
+        self.pierced.push(enemy);
+
+        match self.hit_policy {
+            HitPolicy::Pierce(count) => self.pierced.len() >= count as usize,
+            _ => true,
+        }
+    }
+
+    /// Run one tick of the bullet's script, if it has one, returning any bullets it fires.
+    pub fn run_script(
+        &mut self,
+        position: Position<units::Pixels>,
+        kinematics: &mut Kinematics,
+        faction: Faction,
+        target: Option<Position<units::Pixels>>,
+    ) -> Vec<Bullet> {
+        match self.script.as_mut() {
+            Some(runner) => runner.tick(script::Host { position, kinematics, faction, target }),
+            None => vec![],
+        }
+    }
+
+    /// Whether the bullet's script has removed it from the game, or it has homed in
+    /// for as long as it can.
+    pub fn vanished(&self) -> bool {
+        self.script.as_ref().map(|runner| runner.vanished()).unwrap_or(false)
+            || self.homing.as_ref().is_some_and(Homing::expired)
+    }
+}
+
+impl Homing {
+    /// Homing that turns by up to `turn_rate` and gives up after `lifetime`.
+    pub fn new(turn_rate: units::RadiansPerMs, lifetime: Duration) -> Self {
+        Homing {
+            turn_rate,
+            lifetime,
+            lock: None,
+        }
+    }
+
+    pub fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    pub fn lock(&self) -> Option<Entity> {
+        self.lock
+    }
+
+    /// Chase `enemy` from now on.
+    pub fn lock_on(&mut self, enemy: Entity) {
+        self.lock = Some(enemy);
+    }
+
+    pub fn expired(&self) -> bool {
+        self.lifetime <= Duration::zero()
+    }
+
+    /// Spend `time` turning `kinematics` from `position` towards `target`, if
+    /// there is one, by no more than the turn rate allows.
+    pub fn steer(
+        &mut self,
+        time: Duration,
+        position: Position<units::Pixels>,
+        kinematics: &mut Kinematics,
+        target: Option<Position<units::Pixels>>,
+    ) {
+        self.lifetime -= time;
+
+        let target = match target {
+            Some(target) if target != position => target,
+            _ => return,
+        };
+
+        let heading = kinematics.velocity.angle().0;
+        let wanted = (target - position).angle().0;
+        // The shortest way round, from -π to π.
+        let difference = (wanted - heading + PI).rem_euclid(TAU) - PI;
+        let limit = (self.turn_rate * time).0;
+
+        kinematics.velocity = kinematics.velocity.rotated(units::Radians(difference.clamp(-limit, limit)));
+    }
 }
 
 impl Bullet {
     pub fn new(
-        owner: Owner,
+        faction: Faction,
         kind: Kind,
         position: Position<units::Pixels>,
     ) -> Self {
         Bullet {
-            owner: owner,
-            kind: kind,
-            position: position,
-            dimensions: kind.dimensions(),
+            transform: Transform::new(position, kind.dimensions()),
+            velocity: Velocity(Kinematics::new(faction.default_velocity())),
+            hitbox: Hitbox(kind.hitbox()),
+            faction,
+            projectile: Projectile::new(faction, kind),
+        }
+    }
+
+    /// Have the bullet run a script every tick once it is fired.
+    pub fn with_script(mut self, runner: script::Runner) -> Self {
+        self.projectile.script = Some(Box::new(runner));
+        self
+    }
+
+    /// Only let the part of the bullet inside `hitbox`, relative to its position, hit things.
+    pub fn with_hitbox(mut self, hitbox: collision::Shape) -> Self {
+        self.hitbox = Hitbox(hitbox);
+        self
+    }
+
+    /// Have the bullet chase `enemy`, rather than whichever is nearest.
+    pub fn locked_on(mut self, enemy: Entity) -> Self {
+        if let Some(homing) = self.projectile.homing.as_mut() {
+            homing.lock_on(enemy);
         }
+        self
+    }
+
+    pub fn with_hit_policy(mut self, hit_policy: HitPolicy) -> Self {
+        self.projectile.hit_policy = hit_policy;
+        self
+    }
+
+    /// Fire the bullet from a weapon at `level`, which makes the player's bullets hit harder.
+    pub fn with_level(mut self, level: u32) -> Self {
+        self.projectile.damage = self.projectile.kind.damage(self.faction, level);
+        self
+    }
+
+    /// Replace the bullet's motion, e.g. to send it off at an angle or make it curve.
+    pub fn with_kinematics(mut self, kinematics: Kinematics) -> Self {
+        self.velocity = Velocity(kinematics);
+        self
+    }
+
+    pub fn kinematics(&self) -> Kinematics {
+        self.velocity.0
     }
 
     pub fn position(&self) -> Position<units::Pixels> {
-        self.position
+        self.transform.position
     }
 
-    pub fn owner(&self) -> Owner {
-        self.owner
+    pub fn faction(&self) -> Faction {
+        self.faction
     }
 
     pub fn kind(&self) -> Kind {
-        self.kind
+        self.projectile.kind
     }
-    
-    pub fn reposition(&mut self, time: Duration) {
-        let dx = self.horizontal_velocity(time).distance(time).0;
-        let dy = self.vertical_velocity(time).distance(time).0;
 
-        self.position = Position::new(
-            units::Pixels(self.position.x.value() + dx),
-            units::Pixels(self.position.y.value() + dy),
-        );
+    /// Which way the bullet is flying, clockwise from the positive x axis.
+    pub fn heading(&self) -> units::Radians {
+        self.velocity.0.velocity.angle()
     }
 
-    pub fn hitbox_rect(&self) -> graphics::Rect {
-        graphics::Rect::new(
-            self.position.x.value(),
-            self.position.y.value(),
-            self.dimensions.width.value(),
-            self.dimensions.height.value(),
-        )
+    /// The part of the bullet that hits things, wherever it is.
+    pub fn hitbox(&self) -> collision::Shape {
+        self.hitbox.at(self.transform.position)
     }
 
-    pub fn damage(&self) -> HealthPoints {
-        match (self.owner, self.kind) {
-            (Owner::Player, Kind::Basic) => HealthPoints::new(10),
-            (Owner::Enemy, Kind::Basic)  => HealthPoints::new(5),
-        }
+    /// Run one tick of the bullet's script, if it has one, returning any bullets it fires.
+    pub fn run_script(&mut self, target: Option<Position<units::Pixels>>) -> Vec<Bullet> {
+        self.projectile.run_script(self.transform.position, &mut self.velocity.0, self.faction, target)
     }
 
-    pub fn draw(&self, ctx: &mut Context) -> GameResult {
-        let bullet_rect = graphics::Mesh::new_rectangle(
-            ctx,
-            graphics::DrawMode::fill(),
-            self.hitbox_rect(),
-            graphics::Color::BLUE,
-        )?;
-
-        graphics::draw(ctx, &bullet_rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+    /// Whether the bullet's script has removed it from the game.
+    pub fn vanished(&self) -> bool {
+        self.projectile.vanished()
+    }
+}
 
-        Ok(())
+impl Bundle for Bullet {
+    fn insert(self, world: &mut World, entity: Entity) {
+        world.transforms.insert(entity, self.transform);
+        world.velocities.insert(entity, self.velocity);
+        world.hitboxes.insert(entity, self.hitbox);
+        world.factions.insert(entity, self.faction);
+        world.renderables.insert(entity, Renderable::new(self.projectile.kind.colour(), Layer::Bullets));
+        world.projectiles.insert(entity, self.projectile);
     }
 }
 
 impl Kind {
     pub fn dimensions(&self) -> Dimensions<units::Pixels> {
         match self {
-            Kind::Basic => Dimensions::new(units::Pixels(20.0), units::Pixels(20.0)),
+            Kind::Basic    => Dimensions::new(units::Pixels(20.0), units::Pixels(20.0)),
+            Kind::Homing   => Dimensions::new(units::Pixels(14.0), units::Pixels(14.0)),
+            Kind::Piercing => Dimensions::new(units::Pixels(12.0), units::Pixels(12.0)),
+            Kind::Burst    => Dimensions::new(units::Pixels(16.0), units::Pixels(16.0)),
         }
     }
-}
 
-impl Acceleration<units::PixelsPerMs> for Bullet {
-    fn horizontal_velocity(&self, time: Duration) -> Velocity<units::PixelsPerMs> {
-        Velocity::new(0.0)
+    pub fn colour(&self) -> Colour {
+        match self {
+            Kind::Basic    => (0, 0, 255),
+            Kind::Homing   => (160, 0, 255),
+            Kind::Piercing => (0, 200, 255),
+            Kind::Burst    => (255, 140, 0),
+        }
+    }
+
+    pub fn hit_policy(&self) -> HitPolicy {
+        match self {
+            Kind::Basic | Kind::Homing => HitPolicy::First,
+            Kind::Piercing             => HitPolicy::Pierce(3),
+            Kind::Burst                => HitPolicy::Area { radius: 48.0 },
+        }
+    }
+
+    /// How a bullet of this kind fired by `faction` homes in, if it does. The
+    /// player's turn sharply to hunt enemies down; enemies' turn slowly enough to
+    /// be dodged, and give up sooner.
+    pub fn homing(&self, faction: Faction) -> Option<Homing> {
+        let degrees_per_second = |degrees: f32| units::RadiansPerMs(degrees.to_radians() / 1000.0);
+
+        match (faction, self) {
+            (_, Kind::Basic | Kind::Piercing | Kind::Burst) => None,
+            (Faction::Player, Kind::Homing)                 => Some(Homing::new(degrees_per_second(360.0), Duration::milliseconds(2500))),
+            (Faction::Enemy, Kind::Homing)                  => Some(Homing::new(degrees_per_second(60.0), Duration::milliseconds(2000))),
+        }
+    }
+
+    /// The part of a bullet of this kind that hits things, relative to its top left corner.
+    pub fn hitbox(&self) -> collision::Shape {
+        match self {
+            Kind::Basic    => collision::Shape::Circle { centre: (10.0, 10.0), radius: 8.0 },
+            Kind::Homing   => collision::Shape::Circle { centre: (7.0, 7.0), radius: 5.0 },
+            Kind::Piercing => collision::Shape::Circle { centre: (6.0, 6.0), radius: 5.0 },
+            Kind::Burst    => collision::Shape::Circle { centre: (8.0, 8.0), radius: 6.0 },
+        }
     }
 
-    fn vertical_velocity(&self, time: Duration) -> Velocity<units::PixelsPerMs> {
-        match self.owner {
-            Owner::Enemy  => Velocity::new(0.5),
-            Owner::Player => Velocity::new(-2.0),
+    /// How much a bullet of this kind fired by `faction`, from a weapon at `level`,
+    /// hurts whatever it hits.
+    pub fn damage(&self, faction: Faction, level: u32) -> HealthPoints {
+        match (faction, self) {
+            (Faction::Player, Kind::Basic)    => HealthPoints::new(10 + 2 * level),
+            (Faction::Player, Kind::Homing)   => HealthPoints::new(6 + level),
+            (Faction::Player, Kind::Piercing) => HealthPoints::new(8 + level),
+            (Faction::Player, Kind::Burst)    => HealthPoints::new(8 + level),
+            (Faction::Enemy, _)               => HealthPoints::new(5),
         }
-        
     }
 }
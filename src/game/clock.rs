@@ -0,0 +1,54 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono::offset::Utc;
+
+
+/// A source of the current time.
+/// The frontend reads one to decide how many simulation ticks to run, so swapping
+/// it out lets the game be driven by something other than the wall clock.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The wall clock.
+#[derive(Copy, Clone, Default)]
+pub struct SystemClock;
+
+/// A clock that only moves when told to.
+pub struct ManualClock {
+    now: Cell<DateTime<Utc>>,
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        ManualClock {
+            now: Cell::new(start),
+        }
+    }
+
+    pub fn advance(&self, time: Duration) {
+        self.now.set(self.now.get() + time);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now.get()
+    }
+}
+
+/// A shared clock, so that whoever hands one to the frontend can keep moving it.
+impl<C: Clock + ?Sized> Clock for Rc<C> {
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+}
@@ -0,0 +1,135 @@
+//! The components game objects are made of. Each lives in its own storage in the
+//! `World`, and an object is whichever components its entity has.
+//!
+//! Components only shared by one sort of object live with that object instead:
+//! `player::Pilot`, `enemies::Armament`, `enemies::Reward` and `bullets::Projectile`.
+//! Health is `health::HealthPoints` and a path to follow is a `paths::Follower`.
+
+use crate::physics::{
+    collision,
+    motion::{self, Dimensions, Kinematics, Offset, Position},
+    units::{Pixels, PixelsPerMs},
+};
+
+
+/// An RGB colour.
+pub type Colour = (u8, u8, u8);
+
+/// Where something is and how big it is.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    /// The top left corner.
+    pub position: Position<Pixels>,
+    /// Where it was at the end of the last tick, for drawing in between.
+    pub previous_position: Position<Pixels>,
+    pub dimensions: Dimensions<Pixels>,
+}
+
+/// How something moves under its own power. Anything following a path moves
+/// along that instead, but keeps its velocity for its script to use.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Velocity(pub Kinematics);
+
+/// The part of something that can hit or be hit, relative to its position.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hitbox(pub collision::Shape);
+
+/// Which side something is on. Bullets only hit things on the other side.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Faction {
+    Player,
+    Enemy,
+}
+
+/// How to draw something.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Renderable {
+    pub colour: Colour,
+    pub layer: Layer,
+}
+
+/// What gets drawn over what: each layer is drawn over the ones before it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Player,
+    Enemies,
+    Items,
+    Bullets,
+}
+
+impl Transform {
+    pub fn new(position: Position<Pixels>, dimensions: Dimensions<Pixels>) -> Self {
+        Transform {
+            position,
+            previous_position: position,
+            dimensions,
+        }
+    }
+
+    /// Move to `position` without it counting as movement, e.g. when spawning.
+    pub fn teleport(&mut self, position: Position<Pixels>) {
+        self.position = position;
+        self.previous_position = position;
+    }
+
+    /// Move to `position`, remembering where it was for drawing in between.
+    pub fn move_to(&mut self, position: Position<Pixels>) {
+        self.previous_position = self.position;
+        self.position = position;
+    }
+
+    /// The middle of it.
+    pub fn centre(&self) -> Position<Pixels> {
+        self.position + Offset::new(self.dimensions.width / 2.0, self.dimensions.height / 2.0)
+    }
+
+    /// Where it is `alpha` of the way from its previous position to its current one.
+    pub fn interpolated_position(&self, alpha: f32) -> Position<Pixels> {
+        self.previous_position.lerp(self.position, alpha)
+    }
+
+    /// Where it is now.
+    pub fn rect(&self) -> collision::Rect {
+        self.interpolated_rect(1.0)
+    }
+
+    /// Where to draw it, `alpha` of the way from its previous position to its current one.
+    pub fn interpolated_rect(&self, alpha: f32) -> collision::Rect {
+        let position = self.interpolated_position(alpha);
+
+        collision::Rect::new(
+            position.x.value(),
+            position.y.value(),
+            self.dimensions.width.value(),
+            self.dimensions.height.value(),
+        )
+    }
+}
+
+impl Hitbox {
+    /// A hitbox covering the whole of something `dimensions` in size.
+    pub fn covering(dimensions: Dimensions<Pixels>) -> Self {
+        Hitbox(collision::Rect::new(0.0, 0.0, dimensions.width.value(), dimensions.height.value()).into())
+    }
+
+    /// The hitbox in world space, for something at `position`.
+    pub fn at(&self, position: Position<Pixels>) -> collision::Shape {
+        self.0.translated(position.x.value(), position.y.value())
+    }
+}
+
+impl Faction {
+    /// How fast bullets fired by this faction travel unless told otherwise.
+    pub fn default_velocity(&self) -> motion::Velocity<PixelsPerMs> {
+        match self {
+            Faction::Enemy  => motion::Velocity::new(PixelsPerMs(0.0), PixelsPerMs(0.5)),
+            Faction::Player => motion::Velocity::new(PixelsPerMs(0.0), PixelsPerMs(-2.0)),
+        }
+    }
+}
+
+impl Renderable {
+    pub fn new(colour: Colour, layer: Layer) -> Self {
+        Renderable { colour, layer }
+    }
+}
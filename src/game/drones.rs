@@ -0,0 +1,145 @@
+//! Option drones, or satellites: little gunships that fly in formation with the
+//! player and fire alongside them, one more for each weapon level.
+//!
+//! Each drone trails a little further behind the player than the last, following
+//! where the player was a few ticks ago rather than where they are now.
+
+use crate::{
+    game::{
+        bullets,
+        components::{Faction, Layer, Renderable, Transform},
+        patterns::{Emitter, Pattern},
+        weapons::Weapon,
+        world::{Bundle, Entity, World},
+    },
+    physics::{
+        motion::{Dimensions, Offset, Position},
+        units::{Degrees, Pixels, PixelsPerMs, Radians},
+    },
+};
+
+use chrono::Duration;
+
+use std::collections::VecDeque;
+
+
+/// How many ticks further behind the player each drone is than the one before.
+const TRAIL_DELAY: usize = 4;
+/// How far apart drones fly, side by side, normally and while the player is focused.
+const SPREAD: f32 = 36.0;
+const TIGHT: f32 = 14.0;
+
+/// What makes a drone a drone: whose it is, where it flies in formation and what it fires.
+#[derive(Clone, Debug)]
+pub struct Satellite {
+    owner: Entity,
+    /// The drone's place in the formation, counting from zero.
+    slot: usize,
+    weapon: Weapon,
+}
+
+/// A drone's components, ready to spawn.
+#[derive(Clone, Debug)]
+pub struct Drone {
+    pub transform: Transform,
+    pub satellite: Satellite,
+}
+
+/// Where the middle of something has been over its last few ticks, most recent first,
+/// for drones to follow.
+#[derive(Clone, Debug)]
+pub struct Trail {
+    positions: VecDeque<Position<Pixels>>,
+    length: usize,
+}
+
+impl Satellite {
+    pub fn owner(&self) -> Entity {
+        self.owner
+    }
+
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    /// How many ticks behind its owner the drone flies.
+    pub fn delay(&self) -> usize {
+        (self.slot + 1) * TRAIL_DELAY
+    }
+
+    pub fn weapon_mut(&mut self) -> &mut Weapon {
+        &mut self.weapon
+    }
+}
+
+impl Drone {
+    /// The drone in `slot` of `owner`'s formation, centred on `centre`.
+    pub fn new(owner: Entity, slot: usize, centre: Position<Pixels>) -> Self {
+        let dimensions = Dimensions::new(Pixels(12.0), Pixels(12.0));
+
+        Drone {
+            transform: Transform::new(top_left(centre, dimensions), dimensions),
+            satellite: Satellite {
+                owner,
+                slot,
+                weapon: Weapon::new(Duration::milliseconds(120)).with_stream(
+                    Emitter::new(Faction::Player, bullets::Kind::Basic, Pattern::Spread { count: 1, arc: Radians(0.0) }, PixelsPerMs(2.0), Duration::zero())
+                        .with_heading(Degrees(-90.0)),
+                    Offset::new(dimensions.width / 2.0, Pixels(0.0)),
+                ),
+            },
+        }
+    }
+}
+
+impl Bundle for Drone {
+    fn insert(self, world: &mut World, entity: Entity) {
+        world.transforms.insert(entity, self.transform);
+        world.renderables.insert(entity, Renderable::new((255, 120, 120), Layer::Player));
+        world.satellites.insert(entity, self.satellite);
+    }
+}
+
+impl Trail {
+    /// A trail long enough for a formation of `drones` drones to follow.
+    pub fn new(drones: usize) -> Self {
+        let length = drones * TRAIL_DELAY + 1;
+
+        Trail {
+            positions: VecDeque::with_capacity(length),
+            length,
+        }
+    }
+
+    /// Remember `position` as the latest, forgetting the oldest if the trail is full.
+    pub fn push(&mut self, position: Position<Pixels>) {
+        if self.positions.len() == self.length {
+            self.positions.pop_back();
+        }
+
+        self.positions.push_front(position);
+    }
+
+    /// Where the middle was `ticks` ago, or as long ago as the trail goes back.
+    pub fn at(&self, ticks: usize) -> Option<Position<Pixels>> {
+        self.positions.get(ticks).or_else(|| self.positions.back()).copied()
+    }
+}
+
+/// Where the middle of the drone in `slot` of a formation of `count` flies,
+/// relative to the middle of the player: spread out to the sides normally, and
+/// tucked in close in front of them while they're focused.
+pub fn formation(slot: usize, count: usize, focused: bool) -> Offset<Pixels> {
+    let across = slot as f32 - (count as f32 - 1.0) / 2.0;
+
+    if focused {
+        Offset::new(Pixels(across * TIGHT), Pixels(-24.0))
+    } else {
+        Offset::new(Pixels(across * SPREAD), Pixels(8.0))
+    }
+}
+
+/// Where something `dimensions` in size has to be to have its middle on `centre`.
+pub fn top_left(centre: Position<Pixels>, dimensions: Dimensions<Pixels>) -> Position<Pixels> {
+    Position::new(centre.x - dimensions.width / 2.0, centre.y - dimensions.height / 2.0)
+}
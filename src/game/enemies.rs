@@ -1,35 +1,105 @@
 use crate::{
     game::{
+        boss,
         bullets,
-        health::{Health, HealthPoints},
+        components::{Colour, Faction, Hitbox, Layer, Renderable, Transform, Velocity},
+        health::HealthPoints,
+        items::Item,
+        paths,
+        patterns::{Emitter, Target},
+        rng::Rng,
+        script,
+        stage,
+        world::{Bundle, Entity, World},
     },
     physics::{
+        collision,
         motion,
         units,
     },
 };
 
-use chrono::prelude::*;
 use chrono::Duration;
-use ggez::{
-    graphics::{
-        self,
-        Color,
-    },
-    Context,
-    GameResult,
-};
 
+use serde::Deserialize;
+
+
+/// Something an enemy may leave behind when it is shot down.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Drop {
+    pub item: Item,
+    /// The odds of it being dropped, from 0.0 for never to 1.0 for always.
+    #[serde(default = "always")]
+    pub chance: f32,
+}
+
+/// What an enemy fires, and where from.
+#[derive(Clone)]
+pub struct Armament {
+    pub emitters: Vec<Emitter>,
+    pub script: Option<script::Runner>,
+    /// Where the enemy's bullets come from, relative to its position.
+    pub muzzle: motion::Offset<units::Pixels>,
+}
+
+/// What shooting an enemy down earns.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Reward {
+    pub score: u64,
+    pub drops: Vec<Drop>,
+}
 
+/// An enemy's components, ready to spawn.
 #[derive(Clone)]
 pub struct Enemy {
-    pub position: motion::Position<units::Pixels>,
-    pub dimensions: motion::Dimensions<units::Pixels>,
-   
+    pub transform: Transform,
+    pub velocity: Velocity,
+    /// The path the enemy follows, if it doesn't move under its own velocity.
+    pub route: Option<paths::Follower>,
+    /// The part of the enemy bullets can hit, relative to its position.
+    pub hitbox: Hitbox,
+    pub renderable: Renderable,
+    pub armament: Armament,
+    pub reward: Reward,
+
     health: HealthPoints,
-    bullet_rotation: Vec<bullets::Bullet>,
-    current_bullet_index: usize,
-    last_fired: DateTime<Utc>,
+    phases: Option<boss::Phases>,
+}
+
+impl Armament {
+    /// Where the bullets of an enemy at `position` come from.
+    pub fn muzzle_position(&self, position: motion::Position<units::Pixels>) -> motion::Position<units::Pixels> {
+        position + self.muzzle
+    }
+
+    /// Advance the emitters and script of an enemy at `position`, collecting any
+    /// bullets they fire this tick. Aimed patterns are aimed at `target`.
+    pub fn fire(
+        &mut self,
+        time_since_last_tick: Duration,
+        position: motion::Position<units::Pixels>,
+        kinematics: &mut motion::Kinematics,
+        target: Option<Target>,
+        rng: &mut Rng,
+    ) -> Vec<bullets::Bullet> {
+        let muzzle = self.muzzle_position(position);
+
+        let mut volley: Vec<bullets::Bullet> = self.emitters
+            .iter_mut()
+            .flat_map(|emitter| emitter.update(time_since_last_tick, muzzle, target, rng))
+            .collect();
+
+        if let Some(runner) = self.script.as_mut() {
+            volley.extend(runner.tick(script::Host {
+                position: muzzle,
+                kinematics,
+                faction: Faction::Enemy,
+                target: target.map(|target| target.position),
+            }));
+        }
+
+        volley
+    }
 }
 
 impl Enemy {
@@ -37,75 +107,173 @@ impl Enemy {
         pos: motion::Position<units::Pixels>,
         dim: motion::Dimensions<units::Pixels>,
         health: HealthPoints,
-        bullets: Vec<bullets::Bullet>,
+        emitters: Vec<Emitter>,
     ) -> Self {
         Enemy {
-            position: pos,
-            dimensions: dim,
-            health: health,
-            bullet_rotation: bullets,
-            current_bullet_index: 0usize,
-            last_fired: Utc::now(),
+            transform: Transform::new(pos, dim),
+            velocity: Velocity(motion::Kinematics::stationary()),
+            route: None,
+            hitbox: Hitbox::covering(dim),
+            renderable: Renderable::new((255, 0, 255), Layer::Enemies),
+            armament: Armament {
+                emitters,
+                script: None,
+                muzzle: motion::Offset::new(dim.width / 2.0, dim.height),
+            },
+            reward: Reward::default(),
+            health,
+            phases: None,
         }
     }
 
-    pub fn hitbox_rect(&self) -> graphics::Rect {
-        graphics::Rect::new(
-            self.position.x.value(),
-            self.position.y.value(),
-            self.dimensions.width.value(),
-            self.dimensions.height.value(),
-        )
+    /// Only let bullets hit the part of the enemy inside `hitbox`, relative to its position.
+    pub fn with_hitbox(mut self, hitbox: collision::Shape) -> Self {
+        self.hitbox = Hitbox(hitbox);
+        self
     }
 
-    pub fn fire_bullet(&mut self) -> Option<bullets::Bullet> {
-        let time_since_last_update = Utc::now() - self.last_fired;
-
-        if time_since_last_update.num_milliseconds() / 500 > 0 {
-            let bullet = self.bullet_rotation[self.current_bullet_index].clone();
+    pub fn with_colour(mut self, colour: Colour) -> Self {
+        self.renderable.colour = colour;
+        self
+    }
 
-            self.last_fired = Utc::now();
-            self.current_bullet_index = (self.current_bullet_index + 1) % self.bullet_rotation.len();
+    /// Make shooting the enemy down worth `score` points.
+    pub fn with_score(mut self, score: u64) -> Self {
+        self.reward.score = score;
+        self
+    }
 
-            Some(bullet)
-        } else {
-            None
-        }
+    pub fn with_drops(mut self, drops: Vec<Drop>) -> Self {
+        self.reward.drops = drops;
+        self
     }
 
-    pub fn draw(&self, ctx: &mut Context) -> GameResult {
-        let position = graphics::Rect::new(
-            self.position.x.value(),
-            self.position.y.value(),
-            self.dimensions.width.value(),
-            self.dimensions.height.value(),
+    /// Make the enemy a boss, fighting through each of `phases` in turn.
+    pub fn with_phases(mut self, phases: Vec<boss::Phase>) -> Self {
+        let phases = boss::Phases::new(phases);
+
+        enter_phase(
+            phases.current(),
+            self.transform.position,
+            &mut self.health,
+            &mut self.armament,
+            &mut self.velocity,
+            &mut self.route,
         );
+        self.phases = Some(phases);
+        self
+    }
+
+    /// The boss's progress through its fight, or `None` if the enemy isn't a boss.
+    pub fn phases(&self) -> Option<&boss::Phases> {
+        self.phases.as_ref()
+    }
+
+    pub fn is_boss(&self) -> bool {
+        self.phases.is_some()
+    }
 
-        let enemy_rect = graphics::Mesh::new_rectangle(
-            ctx,
-            graphics::DrawMode::fill(),
-            position,
-            Color::MAGENTA,
-        )?;
+    pub fn score(&self) -> u64 {
+        self.reward.score
+    }
+
+    pub fn drops(&self) -> &[Drop] {
+        &self.reward.drops
+    }
 
-        graphics::draw(ctx, &enemy_rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+    /// Fire from `muzzle` instead of the middle of the enemy's bottom edge.
+    pub fn with_muzzle(mut self, muzzle: motion::Offset<units::Pixels>) -> Self {
+        self.armament.muzzle = muzzle;
+        self
+    }
+
+    /// Have the enemy run `script` every tick, alongside any emitters it has.
+    pub fn with_script(mut self, script: &script::Script) -> Self {
+        self.armament.script = Some(script::Runner::new(script));
+        self
+    }
 
-        Ok(())
+    /// Have the enemy follow `path` instead of moving under its own velocity,
+    /// starting from wherever the path starts.
+    pub fn with_path(mut self, path: paths::Path) -> Self {
+        self.transform.teleport(path.start());
+        self.route = Some(paths::Follower::new(path));
+        self
     }
+
+    /// Where the enemy's bullets come from, wherever it is.
+    pub fn muzzle_position(&self) -> motion::Position<units::Pixels> {
+        self.armament.muzzle_position(self.transform.position)
+    }
+
 }
 
-impl Health for Enemy {
-    fn health(&self) -> HealthPoints {
-        self.health
+impl Bundle for Enemy {
+    fn insert(self, world: &mut World, entity: Entity) {
+        world.transforms.insert(entity, self.transform);
+        world.velocities.insert(entity, self.velocity);
+        if let Some(route) = self.route {
+            world.routes.insert(entity, route);
+        }
+        world.hitboxes.insert(entity, self.hitbox);
+        world.healths.insert(entity, self.health);
+        world.factions.insert(entity, Faction::Enemy);
+        world.renderables.insert(entity, self.renderable);
+        world.armaments.insert(entity, self.armament);
+        world.rewards.insert(entity, self.reward);
+        if let Some(phases) = self.phases {
+            world.bosses.insert(entity, phases);
+        }
     }
+}
 
-    fn restore_health(&mut self, _amt: HealthPoints) -> HealthPoints {
-        // Enemies cannot heal
-        self.health
+/// Run down the clock on a boss's current phase, moving on to the next one if
+/// this one has been shot through or has run out of time. Running out the clock
+/// on the last phase ends the fight as if the boss had been shot down.
+///
+/// Returns whether a phase ended this tick.
+pub fn update_phase(
+    phases: &mut boss::Phases,
+    time: Duration,
+    position: motion::Position<units::Pixels>,
+    health: &mut HealthPoints,
+    armament: &mut Armament,
+    velocity: &mut Velocity,
+    route: &mut Option<paths::Follower>,
+) -> bool {
+    let expired = phases.advance(time);
+    if !expired && !health.empty() {
+        return false;
     }
 
-    fn take_damage(&mut self, amount: HealthPoints) -> HealthPoints {
-        self.health = self.health - amount;
-        self.health
+    match phases.next_phase() {
+        Some(phase) => enter_phase(phase, position, health, armament, velocity, route),
+        None => *health = *health - HealthPoints::new(health.current()),
     }
+
+    true
+}
+
+/// Take on the health, weapons and movement of a boss's `phase`.
+fn enter_phase(
+    phase: &boss::Phase,
+    position: motion::Position<units::Pixels>,
+    health: &mut HealthPoints,
+    armament: &mut Armament,
+    velocity: &mut Velocity,
+    route: &mut Option<paths::Follower>,
+) {
+    *health = HealthPoints::new(phase.health);
+    armament.emitters = phase.emitters.clone();
+    armament.script = phase.script.as_ref().map(script::Runner::new);
+    *velocity = Velocity(motion::Kinematics::stationary());
+    *route = if phase.path.is_empty() {
+        None
+    } else {
+        Some(paths::Follower::new(stage::build_path(position, &phase.path)))
+    };
+}
+
+fn always() -> f32 {
+    1.0
 }
@@ -22,6 +22,14 @@ impl HealthPoints {
         }
     }
 
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    pub fn maximum(&self) -> u32 {
+        self.maximum
+    }
+
     pub fn empty(&self) -> bool {
         self.current == 0u32
     }
@@ -46,15 +54,26 @@ impl Sub for HealthPoints {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        let new_amount = if other.current > self.current {
-            0u32
-        } else {
-            self.current - other.current
-        };
-
         HealthPoints {
-            current: new_amount,
+            current: self.current.saturating_sub(other.current),
             maximum: self.maximum,
         }
     }
 }
+
+/// The health components in the `World` are dealt damage like anything else with health.
+impl Health for HealthPoints {
+    fn health(&self) -> HealthPoints {
+        *self
+    }
+
+    fn restore_health(&mut self, amount: HealthPoints) -> HealthPoints {
+        *self = *self + amount;
+        *self
+    }
+
+    fn take_damage(&mut self, amount: HealthPoints) -> HealthPoints {
+        *self = *self - amount;
+        *self
+    }
+}
@@ -0,0 +1,166 @@
+//! Items that enemies drop when they're shot down, which drift down the screen
+//! for the player to collect.
+//!
+//! Items are drawn in to the player while they're above the point of collection,
+//! a line near the top of the screen, and any within reach while they're focused.
+
+use crate::{
+    game::{
+        components::{Colour, Hitbox, Layer, Renderable, Transform, Velocity},
+        world::{Bundle, Entity, World},
+    },
+    physics::{
+        motion::{self, Dimensions, Kinematics, Position},
+        units::{Pixels, PixelsPerMs},
+    },
+};
+
+use serde::Deserialize;
+
+use std::convert::TryFrom;
+
+
+/// How fast items drift down the screen.
+const FALL_SPEED: f32 = 0.1;
+/// How fast items fly to the player once drawn in.
+const MAGNET_SPEED: f32 = 0.8;
+
+/// How far down the screen the point of collection is, as a fraction of its height.
+pub const COLLECTION_LINE: f32 = 0.25;
+/// How close items have to be to a focused player to be drawn in.
+pub const MAGNET_RADIUS: f32 = 96.0;
+/// What a points item adds to the score.
+pub const POINTS: u64 = 500;
+
+/// The sorts of item there are. In stage files they're named in lower case, e.g.
+/// `Drop(item: "power")`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Item {
+    /// Raises the player's weapon level.
+    Power,
+    Points,
+    /// An extra life.
+    Life,
+    /// An extra bomb.
+    Bomb,
+}
+
+/// What makes an item an item: which sort it is and whether it's been drawn in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pickup {
+    item: Item,
+    magnetised: bool,
+}
+
+/// An item's components, ready to spawn.
+#[derive(Clone, Debug)]
+pub struct Collectible {
+    pub transform: Transform,
+    pub velocity: Velocity,
+    pub pickup: Pickup,
+}
+
+impl Item {
+    /// The item called `name` in stage files, if there is one.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "power"  => Some(Item::Power),
+            "points" => Some(Item::Points),
+            "life"   => Some(Item::Life),
+            "bomb"   => Some(Item::Bomb),
+            _        => None,
+        }
+    }
+
+    pub fn dimensions(&self) -> Dimensions<Pixels> {
+        match self {
+            Item::Power | Item::Points => Dimensions::new(Pixels(12.0), Pixels(12.0)),
+            Item::Life | Item::Bomb    => Dimensions::new(Pixels(18.0), Pixels(18.0)),
+        }
+    }
+
+    pub fn colour(&self) -> Colour {
+        match self {
+            Item::Power  => (220, 0, 0),
+            Item::Points => (0, 80, 220),
+            Item::Life   => (255, 0, 200),
+            Item::Bomb   => (0, 180, 0),
+        }
+    }
+}
+
+impl TryFrom<String> for Item {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Item::named(&name).ok_or_else(|| format!("unknown item `{}`", name))
+    }
+}
+
+impl Pickup {
+    pub fn new(item: Item) -> Self {
+        Pickup { item, magnetised: false }
+    }
+
+    pub fn item(&self) -> Item {
+        self.item
+    }
+
+    /// Whether the item is flying to the player rather than drifting down.
+    pub fn is_magnetised(&self) -> bool {
+        self.magnetised
+    }
+
+    /// Draw the item in to the player from now on.
+    pub fn magnetise(&mut self) {
+        self.magnetised = true;
+    }
+
+    /// How the item moves: towards `player` if it's been drawn in and they're
+    /// there to fly to, otherwise down the screen.
+    pub fn velocity(&self, position: Position<Pixels>, player: Option<Position<Pixels>>) -> motion::Velocity<PixelsPerMs> {
+        match player {
+            Some(player) if self.magnetised => {
+                let offset = player - position;
+
+                if offset.length().value() < f32::EPSILON {
+                    motion::Velocity::zero()
+                } else {
+                    motion::Velocity::from_angle(offset.angle(), PixelsPerMs(MAGNET_SPEED))
+                }
+            },
+            _ => falling(),
+        }
+    }
+}
+
+impl Collectible {
+    /// An `item` centred on `centre`, drifting down.
+    pub fn new(item: Item, centre: Position<Pixels>) -> Self {
+        let dimensions = item.dimensions();
+        let position = Position::new(centre.x - dimensions.width / 2.0, centre.y - dimensions.height / 2.0);
+
+        Collectible {
+            transform: Transform::new(position, dimensions),
+            velocity: Velocity(Kinematics::new(falling())),
+            pickup: Pickup::new(item),
+        }
+    }
+}
+
+impl Bundle for Collectible {
+    fn insert(self, world: &mut World, entity: Entity) {
+        let item = self.pickup.item;
+
+        world.hitboxes.insert(entity, Hitbox::covering(self.transform.dimensions));
+        world.transforms.insert(entity, self.transform);
+        world.velocities.insert(entity, self.velocity);
+        world.renderables.insert(entity, Renderable::new(item.colour(), Layer::Items));
+        world.pickups.insert(entity, self.pickup);
+    }
+}
+
+fn falling() -> motion::Velocity<PixelsPerMs> {
+    motion::Velocity::new(PixelsPerMs(0.0), PixelsPerMs(FALL_SPEED))
+}
@@ -0,0 +1,177 @@
+use crate::physics::{
+    motion::Position,
+    units::Pixels,
+};
+
+use chrono::Duration;
+
+
+/// How long the player's explosion lasts before they respawn.
+pub const RESPAWN_DELAY: Duration = Duration::milliseconds(1500);
+/// How long the player is immune to bullets after respawning.
+pub const INVULNERABILITY: Duration = Duration::milliseconds(2500);
+/// How long after a fatal hit the player can still bomb their way out of dying.
+pub const DEATHBOMB_WINDOW: Duration = Duration::milliseconds(150);
+/// How long each blink lasts while the player is invulnerable.
+const BLINK: Duration = Duration::milliseconds(100);
+
+/// How many more times the player can die, and what has become of their current life.
+#[derive(Clone, Debug)]
+pub struct Lives {
+    /// Counting the current one, unless the player is dead.
+    remaining: u32,
+    life: Life,
+    /// Where the player comes back after dying.
+    spawn_point: Position<Pixels>,
+}
+
+/// The stages of one of the player's lives.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Life {
+    Alive,
+    /// Fatally hit, and about to die unless the player bombs in time.
+    Hit { dies_in: Duration },
+    /// Blinking and immune to bullets for a while after respawning or bombing.
+    Invulnerable { remaining: Duration },
+    /// Exploding, then respawning if there are any lives left.
+    Dead { respawn_in: Duration },
+    /// Died with no lives left.
+    GameOver,
+}
+
+/// What happened to a life over one `Lives::update`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Respawned,
+    GameOver,
+}
+
+impl Lives {
+    /// `count` lives, respawning at `spawn_point`.
+    pub fn new(count: u32, spawn_point: Position<Pixels>) -> Self {
+        Lives {
+            remaining: count,
+            life: Life::Alive,
+            spawn_point,
+        }
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    pub fn life(&self) -> Life {
+        self.life
+    }
+
+    pub fn spawn_point(&self) -> Position<Pixels> {
+        self.spawn_point
+    }
+
+    /// Whether the player is in the game, invulnerable or not.
+    pub fn is_alive(&self) -> bool {
+        matches!(self.life, Life::Alive | Life::Hit { .. } | Life::Invulnerable { .. })
+    }
+
+    /// Whether the player has been fatally hit, but can still bomb to survive.
+    pub fn is_hit(&self) -> bool {
+        matches!(self.life, Life::Hit { .. })
+    }
+
+    /// Whether bullets can hit the player.
+    pub fn is_vulnerable(&self) -> bool {
+        self.life == Life::Alive
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.life == Life::GameOver
+    }
+
+    /// Whether the player should be drawn: not while dead, and only every other
+    /// blink while invulnerable.
+    pub fn is_visible(&self) -> bool {
+        match self.life {
+            Life::Alive | Life::Hit { .. } => true,
+            Life::Invulnerable { remaining } => (remaining.num_milliseconds() / BLINK.num_milliseconds()) % 2 == 0,
+            Life::Dead { .. } | Life::GameOver => false,
+        }
+    }
+
+    /// How far through the player's explosion is, from 0 to 1, if they're exploding.
+    pub fn explosion(&self) -> Option<f32> {
+        match self.life {
+            Life::Dead { respawn_in } => {
+                let elapsed = RESPAWN_DELAY - respawn_in;
+
+                Some(elapsed.num_milliseconds() as f32 / RESPAWN_DELAY.num_milliseconds() as f32)
+            },
+            _ => None,
+        }
+    }
+
+    /// Add an extra life.
+    pub fn gain(&mut self) {
+        self.remaining += 1;
+    }
+
+    /// Take a fatal hit, which kills the player once the deathbomb window closes.
+    pub fn hit(&mut self) {
+        if self.is_vulnerable() {
+            self.life = Life::Hit { dies_in: DEATHBOMB_WINDOW };
+        }
+    }
+
+    /// Make the player immune to bullets for at least `time`, saving them if
+    /// they've just been hit.
+    pub fn shield(&mut self, time: Duration) {
+        self.life = match self.life {
+            Life::Invulnerable { remaining } => Life::Invulnerable { remaining: remaining.max(time) },
+            Life::Alive | Life::Hit { .. } => Life::Invulnerable { remaining: time },
+            life => life,
+        };
+    }
+
+    /// Lose the current life, if the player is alive to lose it.
+    pub fn die(&mut self) {
+        if self.is_alive() {
+            self.remaining = self.remaining.saturating_sub(1);
+            self.life = Life::Dead { respawn_in: RESPAWN_DELAY };
+        }
+    }
+
+    /// Count down to the player's death, the end of their explosion or of their
+    /// invulnerability, returning whether they respawned or ran out of lives.
+    pub fn update(&mut self, time: Duration) -> Option<Event> {
+        match self.life {
+            Life::Hit { dies_in } if dies_in > time => {
+                self.life = Life::Hit { dies_in: dies_in - time };
+                None
+            },
+            Life::Hit { .. } => {
+                self.die();
+                None
+            },
+            Life::Invulnerable { remaining } if remaining > time => {
+                self.life = Life::Invulnerable { remaining: remaining - time };
+                None
+            },
+            Life::Invulnerable { .. } => {
+                self.life = Life::Alive;
+                None
+            },
+            Life::Dead { respawn_in } if respawn_in > time => {
+                self.life = Life::Dead { respawn_in: respawn_in - time };
+                None
+            },
+            Life::Dead { .. } if self.remaining > 0 => {
+                self.life = Life::Invulnerable { remaining: INVULNERABILITY };
+                Some(Event::Respawned)
+            },
+            Life::Dead { .. } => {
+                self.life = Life::GameOver;
+                Some(Event::GameOver)
+            },
+            Life::Alive | Life::GameOver => None,
+        }
+    }
+}
@@ -1,5 +1,27 @@
+pub mod archetypes;
+pub mod arena;
+pub mod bombs;
+pub mod boss;
 pub mod bullets;
+pub mod clock;
+pub mod components;
+pub mod drones;
 pub mod enemies;
 pub mod health;
+pub mod items;
+pub mod lives;
+pub mod paths;
+pub mod patterns;
+pub mod render;
+pub mod replay;
+pub mod rng;
+pub mod script;
+pub mod simulation;
+pub mod stage;
 pub mod state;
+pub mod systems;
 pub mod player;
+pub mod timestep;
+pub mod trace;
+pub mod weapons;
+pub mod world;
@@ -0,0 +1,289 @@
+use crate::physics::{
+    motion::{
+        Easing,
+        Offset,
+        Position,
+    },
+    units::{
+        self,
+        Pixels,
+        PixelsPerMs,
+    },
+};
+
+use std::f32::consts::TAU;
+
+use chrono::Duration;
+
+
+/// A route for an enemy to follow, made of segments joined end to end.
+///
+/// Paths are built up from a starting point, each segment picking up where the
+/// last one finished:
+///
+/// ```
+/// # use glhf::{game::paths::Path, physics::{motion::{Easing, Position}, units::Pixels}};
+/// # use chrono::Duration;
+/// let path = Path::new(Position::new(Pixels(300.0), Pixels(-50.0)))
+///     .line_to(Position::new(Pixels(300.0), Pixels(100.0)), Duration::seconds(1), Easing::EaseOut)
+///     .hover(Duration::seconds(3))
+///     .line_to(Position::new(Pixels(300.0), Pixels(-50.0)), Duration::seconds(1), Easing::EaseIn);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    start: Position<Pixels>,
+    segments: Vec<Segment>,
+}
+
+/// One stretch of a path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub from: Position<Pixels>,
+    pub shape: Shape,
+    pub duration: Duration,
+    /// How progress along the segment speeds up and slows down over its duration.
+    pub easing: Easing,
+}
+
+/// The way a segment gets from its start to its end.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Straight to `to`.
+    Line { to: Position<Pixels> },
+    /// A cubic Bézier curve to `to`, pulled towards the two control points.
+    Bezier { first: Position<Pixels>, second: Position<Pixels>, to: Position<Pixels> },
+    /// A Catmull-Rom spline passing through every one of `points` in turn.
+    Spline { points: Vec<Position<Pixels>> },
+    /// Along `travel`, swaying `amplitude` to either side of it once every `period`.
+    Sine { travel: Offset<Pixels>, amplitude: Pixels, period: Duration },
+    /// Staying put.
+    Hover,
+}
+
+/// Walks an enemy along a path one tick at a time.
+#[derive(Clone, Debug)]
+pub struct Follower {
+    path: Path,
+    segment: usize,
+    elapsed: Duration,
+}
+
+impl Path {
+    pub fn new(start: Position<Pixels>) -> Self {
+        Path {
+            start,
+            segments: vec![],
+        }
+    }
+
+    /// Fly in from `entrance` to `station`, wait there for `hover`, then leave for `exit`,
+    /// travelling at `speed` on the way in and out.
+    pub fn enter_hover_exit(
+        entrance: Position<Pixels>,
+        station: Position<Pixels>,
+        hover: Duration,
+        exit: Position<Pixels>,
+        speed: PixelsPerMs,
+    ) -> Self {
+        Path::new(entrance)
+            .line_at(station, speed, Easing::EaseOut)
+            .hover(hover)
+            .line_at(exit, speed, Easing::EaseIn)
+    }
+
+    /// Visit each of `waypoints` in turn in straight lines at a steady `speed`.
+    pub fn waypoints(start: Position<Pixels>, waypoints: &[Position<Pixels>], speed: PixelsPerMs) -> Self {
+        waypoints.iter().fold(Path::new(start), |path, waypoint| path.line_at(*waypoint, speed, Easing::Linear))
+    }
+
+    pub fn start(&self) -> Position<Pixels> {
+        self.start
+    }
+
+    /// Where the last segment finishes.
+    pub fn end(&self) -> Position<Pixels> {
+        self.segments.last()
+            .map(Segment::end)
+            .unwrap_or(self.start)
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// How long the whole path takes to follow.
+    pub fn duration(&self) -> Duration {
+        self.segments.iter().fold(Duration::zero(), |total, segment| total + segment.duration)
+    }
+
+    pub fn then(mut self, shape: Shape, duration: Duration, easing: Easing) -> Self {
+        let from = self.end();
+        self.segments.push(Segment { from, shape, duration, easing });
+        self
+    }
+
+    pub fn line_to(self, to: Position<Pixels>, duration: Duration, easing: Easing) -> Self {
+        self.then(Shape::Line { to }, duration, easing)
+    }
+
+    /// A straight line to `to`, taking as long as it takes at `speed`.
+    pub fn line_at(self, to: Position<Pixels>, speed: PixelsPerMs, easing: Easing) -> Self {
+        let duration = time_to_cover((to - self.end()).length(), speed);
+
+        self.line_to(to, duration, easing)
+    }
+
+    pub fn bezier_to(
+        self,
+        first: Position<Pixels>,
+        second: Position<Pixels>,
+        to: Position<Pixels>,
+        duration: Duration,
+        easing: Easing,
+    ) -> Self {
+        self.then(Shape::Bezier { first, second, to }, duration, easing)
+    }
+
+    pub fn spline_through(self, points: Vec<Position<Pixels>>, duration: Duration, easing: Easing) -> Self {
+        self.then(Shape::Spline { points }, duration, easing)
+    }
+
+    pub fn sine(self, travel: Offset<Pixels>, amplitude: Pixels, period: Duration, duration: Duration) -> Self {
+        self.then(Shape::Sine { travel, amplitude, period }, duration, Easing::Linear)
+    }
+
+    pub fn hover(self, duration: Duration) -> Self {
+        self.then(Shape::Hover, duration, Easing::Linear)
+    }
+}
+
+impl Segment {
+    pub fn end(&self) -> Position<Pixels> {
+        self.position_at(self.duration)
+    }
+
+    /// Where along the segment an object is after `elapsed`.
+    pub fn position_at(&self, elapsed: Duration) -> Position<Pixels> {
+        let progress = if self.duration > Duration::zero() {
+            self.easing.apply(units::milliseconds(elapsed) / units::milliseconds(self.duration))
+        } else {
+            1.0
+        };
+
+        // Work relative to the start of the segment, since positions can't be scaled.
+        let from = self.from;
+        let relative = |point: Position<Pixels>| point - from;
+
+        let offset = match &self.shape {
+            Shape::Line { to } => relative(*to) * progress,
+
+            Shape::Bezier { first, second, to } => {
+                let rest = 1.0 - progress;
+
+                relative(*first) * (3.0 * rest * rest * progress)
+                    + relative(*second) * (3.0 * rest * progress * progress)
+                    + relative(*to) * (progress * progress * progress)
+            },
+
+            Shape::Spline { points } => {
+                let mut knots = vec![Offset::zero()];
+                knots.extend(points.iter().map(|point| relative(*point)));
+
+                catmull_rom(&knots, progress)
+            },
+
+            Shape::Sine { travel, amplitude, period } => {
+                let length = travel.length().value();
+                let phase = if *period > Duration::zero() {
+                    units::milliseconds(elapsed.min(self.duration)) / units::milliseconds(*period)
+                } else {
+                    0.0
+                };
+
+                let sway = if length > 0.0 {
+                    // Perpendicular to the direction of travel, to its right.
+                    Offset::new(-travel.y, travel.x) * (amplitude.value() * (phase * TAU).sin() / length)
+                } else {
+                    Offset::new(*amplitude * (phase * TAU).sin(), Pixels(0.0))
+                };
+
+                *travel * progress + sway
+            },
+
+            Shape::Hover => Offset::zero(),
+        };
+
+        from + offset
+    }
+}
+
+impl Follower {
+    pub fn new(path: Path) -> Self {
+        Follower {
+            path,
+            segment: 0,
+            elapsed: Duration::zero(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the end of the path has been reached.
+    pub fn finished(&self) -> bool {
+        self.segment >= self.path.segments.len()
+    }
+
+    /// Move `time` further along the path, returning the new position.
+    pub fn advance(&mut self, time: Duration) -> Position<Pixels> {
+        self.elapsed += time;
+
+        while let Some(segment) = self.path.segments.get(self.segment) {
+            if self.elapsed < segment.duration {
+                return segment.position_at(self.elapsed);
+            }
+
+            // Carry any time left over into the next segment.
+            self.elapsed -= segment.duration;
+            self.segment += 1;
+        }
+
+        self.path.end()
+    }
+}
+
+/// How long it takes to cover `distance` at `speed`.
+fn time_to_cover(distance: Pixels, speed: PixelsPerMs) -> Duration {
+    if speed.value() <= 0.0 {
+        return Duration::zero();
+    }
+
+    Duration::microseconds((distance.value() / speed.value() * 1000.0) as i64)
+}
+
+/// A point `progress` of the way along a uniform Catmull-Rom spline through `knots`,
+/// spending an equal share of the progress between each pair of knots.
+fn catmull_rom(knots: &[Offset<Pixels>], progress: f32) -> Offset<Pixels> {
+    let spans = knots.len().saturating_sub(1);
+    if spans == 0 {
+        return knots.first().copied().unwrap_or_else(Offset::zero);
+    }
+
+    let scaled = progress.clamp(0.0, 1.0) * spans as f32;
+    let span = (scaled as usize).min(spans - 1);
+    let t = scaled - span as f32;
+
+    // The ends are repeated so that the curve starts and finishes on the first and last knots.
+    let knot = |index: isize| knots[index.clamp(0, spans as isize) as usize];
+    let index = span as isize;
+    let (p0, p1, p2, p3) = (knot(index - 1), knot(index), knot(index + 1), knot(index + 2));
+
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (-p0 + p1 * 3.0 - p2 * 3.0 + p3) * t3) * 0.5
+}
@@ -0,0 +1,277 @@
+use crate::{
+    game::{
+        bullets,
+        components::Faction,
+        rng::Rng,
+    },
+    physics::{
+        motion::{
+            Kinematics,
+            Offset,
+            Position,
+            Velocity,
+        },
+        units::{
+            Pixels,
+            PixelsPerMs,
+            Radians,
+            RadiansPerMs,
+        },
+    },
+};
+
+use std::f32::consts::TAU;
+
+use chrono::Duration;
+
+use serde::Deserialize;
+
+
+/// The shape of a single volley of bullets.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Pattern {
+    /// `count` bullets fanned evenly across `arc`, centred on the emitter's heading.
+    /// A spread of one is a single straight shot.
+    Spread { count: u32, arc: Radians },
+    /// `count` bullets evenly spaced all the way around.
+    Ring { count: u32 },
+    /// `arms` bullets evenly spaced all the way around, turned a further `step`
+    /// with every volley so that rapid fire traces out a spiral.
+    Spiral { arms: u32, step: Radians },
+    /// A spread centred on the target rather than on the emitter's heading.
+    Aimed { count: u32, arc: Radians },
+    /// `count` bullets at random angles within `arc` of the heading, each with a speed
+    /// up to `speed_variance` faster or slower than the emitter's.
+    Scatter { count: u32, arc: Radians, speed_variance: PixelsPerMs },
+}
+
+/// Where an emitter points its `Aimed` patterns.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum Aim {
+    /// At where the target is right now.
+    #[default]
+    Current,
+    /// At where the target will be by the time the bullet gets there, assuming it
+    /// keeps moving the way it is.
+    Predicted,
+}
+
+/// Something an emitter can aim at.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Target {
+    pub position: Position<Pixels>,
+    pub velocity: Velocity<PixelsPerMs>,
+}
+
+/// Fires a pattern of bullets, either on demand or automatically at a fixed interval.
+#[derive(Clone, Debug)]
+pub struct Emitter {
+    pub pattern: Pattern,
+    pub faction: Faction,
+    pub kind: bullets::Kind,
+    /// How fast the bullets leave the emitter.
+    pub speed: PixelsPerMs,
+    /// The direction the pattern is centred on, clockwise from the positive x axis.
+    pub heading: Radians,
+    /// How quickly the heading turns by itself between volleys.
+    pub rotation_rate: RadiansPerMs,
+    /// How quickly each bullet turns once fired, making it curve.
+    pub curve: RadiansPerMs,
+    /// How long to wait between automatic volleys.
+    pub interval: Duration,
+    pub aim: Aim,
+
+    time_since_fired: Duration,
+}
+
+impl Emitter {
+    /// An emitter firing `pattern` straight down with no rotation, once every `interval`.
+    pub fn new(
+        faction: Faction,
+        kind: bullets::Kind,
+        pattern: Pattern,
+        speed: PixelsPerMs,
+        interval: Duration,
+    ) -> Self {
+        Emitter {
+            pattern,
+            faction,
+            kind,
+            speed,
+            heading: Radians(TAU / 4.0),
+            rotation_rate: RadiansPerMs(0.0),
+            curve: RadiansPerMs(0.0),
+            interval,
+            aim: Aim::Current,
+            time_since_fired: Duration::zero(),
+        }
+    }
+
+    pub fn with_heading<A: Into<Radians>>(mut self, heading: A) -> Self {
+        self.heading = heading.into();
+        self
+    }
+
+    pub fn with_rotation_rate(mut self, rotation_rate: RadiansPerMs) -> Self {
+        self.rotation_rate = rotation_rate;
+        self
+    }
+
+    pub fn with_curve(mut self, curve: RadiansPerMs) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn with_aim(mut self, aim: Aim) -> Self {
+        self.aim = aim;
+        self
+    }
+
+    /// Advance the emitter's timer and rotation, firing a volley centred on `origin`
+    /// if enough time has passed since the last one.
+    pub fn update(
+        &mut self,
+        time: Duration,
+        origin: Position<Pixels>,
+        target: Option<Target>,
+        rng: &mut Rng,
+    ) -> Vec<bullets::Bullet> {
+        self.heading += self.rotation_rate * time;
+        self.time_since_fired += time;
+
+        if self.time_since_fired >= self.interval {
+            self.time_since_fired -= self.interval;
+            self.fire(origin, target, rng)
+        } else {
+            vec![]
+        }
+    }
+
+    /// Fire one volley right away, each bullet centred on `origin`.
+    /// Aimed patterns head for `target` according to the emitter's `aim`, or fall back to the emitter's heading without one.
+    pub fn fire(
+        &mut self,
+        origin: Position<Pixels>,
+        target: Option<Target>,
+        rng: &mut Rng,
+    ) -> Vec<bullets::Bullet> {
+        let (faction, kind, curve) = (self.faction, self.kind, self.curve);
+        let size = kind.dimensions();
+        let corner = origin - Offset::new(size.width / 2.0, size.height / 2.0);
+        let bullet = |angle: Radians, speed: PixelsPerMs| {
+            let kinematics = Kinematics::new(Velocity::from_angle(angle, speed))
+                .with_angular_velocity(curve);
+
+            bullets::Bullet::new(faction, kind, corner).with_kinematics(kinematics)
+        };
+
+        match self.pattern {
+            Pattern::Spread { count, arc } => {
+                fan(self.heading, arc, count).map(|angle| bullet(angle, self.speed)).collect()
+            },
+
+            Pattern::Ring { count } => {
+                ring(self.heading, count).map(|angle| bullet(angle, self.speed)).collect()
+            },
+
+            Pattern::Spiral { arms, step } => {
+                let volley = ring(self.heading, arms).map(|angle| bullet(angle, self.speed)).collect();
+                self.heading += step;
+
+                volley
+            },
+
+            Pattern::Aimed { count, arc } => {
+                let heading = target
+                    .map(|target| (self.aim_point(origin, target) - origin).angle())
+                    .unwrap_or(self.heading);
+
+                fan(heading, arc, count).map(|angle| bullet(angle, self.speed)).collect()
+            },
+
+            Pattern::Scatter { count, arc, speed_variance } => {
+                (0..count)
+                    .map(|_| {
+                        let angle = self.heading + Radians(rng.range(-0.5, 0.5) * arc.value());
+                        let speed = self.speed + PixelsPerMs(rng.range(-1.0, 1.0) * speed_variance.value());
+
+                        bullet(angle, speed)
+                    })
+                    .collect()
+            },
+        }
+    }
+
+    /// The point to aim at when firing from `origin` at `target`.
+    fn aim_point(&self, origin: Position<Pixels>, target: Target) -> Position<Pixels> {
+        match self.aim {
+            Aim::Current   => target.position,
+            Aim::Predicted => intercept(origin, target, self.speed).unwrap_or(target.position),
+        }
+    }
+}
+
+impl Target {
+    pub fn new(position: Position<Pixels>, velocity: Velocity<PixelsPerMs>) -> Self {
+        Target { position, velocity }
+    }
+
+    /// A target that is standing still at `position`.
+    pub fn stationary(position: Position<Pixels>) -> Self {
+        Target::new(position, Velocity::zero())
+    }
+}
+
+/// Where a bullet fired from `origin` at `speed` meets `target`, if it can catch it at all.
+///
+/// Solves `|relative + velocity * t| = speed * t` for the soonest time `t` in the future.
+fn intercept(origin: Position<Pixels>, target: Target, speed: PixelsPerMs) -> Option<Position<Pixels>> {
+    let relative = target.position - origin;
+    let (rx, ry) = (relative.x.value(), relative.y.value());
+    let (vx, vy) = (target.velocity.x.value(), target.velocity.y.value());
+
+    let a = vx * vx + vy * vy - speed.value() * speed.value();
+    let b = 2.0 * (rx * vx + ry * vy);
+    let c = rx * rx + ry * ry;
+
+    let time = if a.abs() < f32::EPSILON {
+        // The bullet is exactly as fast as the target, so the equation is linear.
+        Some(-c / b).filter(|_| b < 0.0)
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let root = discriminant.sqrt();
+        let (first, second) = ((-b - root) / (2.0 * a), (-b + root) / (2.0 * a));
+        let (soonest, latest) = (first.min(second), first.max(second));
+
+        if soonest >= 0.0 {
+            Some(soonest)
+        } else {
+            Some(latest).filter(|latest| *latest >= 0.0)
+        }
+    }?;
+
+    Some(target.position + target.velocity * Duration::microseconds((time * 1000.0) as i64))
+}
+
+/// `count` angles spread evenly across `arc`, centred on `heading`.
+fn fan(heading: Radians, arc: Radians, count: u32) -> impl Iterator<Item = Radians> {
+    let gap = if count > 1 {
+        arc / (count - 1) as f32
+    } else {
+        Radians(0.0)
+    };
+    let first = heading - gap * (count.saturating_sub(1) as f32 / 2.0);
+
+    (0..count).map(move |index| first + gap * index as f32)
+}
+
+/// `count` angles spaced evenly all the way around, starting at `heading`.
+fn ring(heading: Radians, count: u32) -> impl Iterator<Item = Radians> {
+    let gap = Radians(TAU / count.max(1) as f32);
+
+    (0..count).map(move |index| heading + gap * index as f32)
+}
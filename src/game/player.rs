@@ -1,89 +1,132 @@
 use crate::{
     config::ui::UI,
-    game::health::{Health, HealthPoints},
+    game::{
+        bombs::Bombs,
+        bullets,
+        components::{self, Faction, Hitbox, Layer, Renderable, Transform},
+        drones::Trail,
+        health::HealthPoints,
+        lives::Lives,
+        patterns::{Emitter, Pattern, Target},
+        rng::Rng,
+        weapons::Weapon,
+        world::{Bundle, Entity, World},
+    },
+    physics::collision,
     physics::motion::*,
     physics::units,
 };
 
-use chrono::prelude::*;
 use chrono::Duration;
 
-use ggez::{
-    event::KeyCode,
-    graphics::{
-        self,
-        Color
-    },
-    Context,
-    GameResult,
-};
 
-/// The player's state.
-pub struct Player {
-    pub position: Position<units::Pixels>,
-    pub dimensions: Dimensions<units::Pixels>,
+/// How fast the player moves, normally and while focused.
+const SPEED: f32 = 0.5;
+const FOCUSED_SPEED: f32 = 0.2;
+
+/// How many lives the player starts with.
+const LIVES: u32 = 3;
+/// How many bombs the player starts with, and can hold at most.
+const BOMBS: u32 = 3;
+const MAX_BOMBS: u32 = 8;
 
+/// How many power items it takes to go up a weapon level, and the highest level there is.
+pub const POWER_PER_LEVEL: u32 = 8;
+pub const MAX_LEVEL: u32 = 4;
+
+/// What makes the player the player: the directions they're moving in and their weapons.
+#[derive(Clone, Debug)]
+pub struct Pilot {
     horizontal_direction: Direction,
     vertical_direction: Direction,
-
-    health: HealthPoints,
+    /// Whether the player is holding focus, moving slowly for precise dodging.
+    focused: bool,
+    /// Whether the player is holding down the trigger.
+    firing: bool,
+    weapon: Weapon,
+    /// What to shoot while focused, if not the usual weapon.
+    focused_weapon: Option<Weapon>,
+    /// How many power items the player has collected, which sets their weapon level.
+    power: u32,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub enum KeyPress {
-    Pressed,
-    Released,
+/// The player's components, ready to spawn.
+pub struct Player {
+    pub transform: Transform,
+    /// The part of the player that bullets can hit, relative to its position.
+    pub hitbox: Hitbox,
+    pub pilot: Pilot,
+    pub lives: Lives,
+    pub bombs: Bombs,
+
+    health: HealthPoints,
 }
 
 /// The various actions the player can take.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Action {
     Move(Direction),
     StopMoving(Direction),
-    Shoot,
+    /// Hold down or let go of the trigger, firing at the weapon's rate in between.
+    StartFiring,
+    StopFiring,
+    /// Start or stop moving slowly, showing the hitbox and concentrating fire.
+    Focus,
+    Unfocus,
+    /// Clear the screen of enemy bullets and hurt every enemy, if there are any bombs left.
+    Bomb,
 }
 
-impl Player {
-    pub fn new(ui: &UI) -> Self {
-        Player {
-            position: Position::new(
-                  units::Pixels(ui.width / 2.0 - 12.0),
-                  units::Pixels(ui.height - 64.0)
-            ),
-            dimensions: Dimensions::new(
-                units::Pixels(24.0),
-                units::Pixels(32.0)
-            ),
+impl Pilot {
+    pub fn new(weapon: Weapon) -> Self {
+        Pilot {
             horizontal_direction: Direction::Stationary,
             vertical_direction: Direction::Stationary,
-            health: HealthPoints::new(250),
+            focused: false,
+            firing: false,
+            weapon,
+            focused_weapon: None,
+            power: 0,
         }
     }
 
-    pub fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let position = graphics::Rect::new(
-            self.position.x.value(),
-            self.position.y.value(),
-            self.dimensions.width.value(),
-            self.dimensions.height.value(),
-        );
- 
-        let player_rect = graphics::Mesh::new_rectangle(
-            ctx,
-            graphics::DrawMode::fill(),
-            position,
-            Color::RED,
-        )?;
-
-        graphics::draw(ctx, &player_rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
-
-        Ok(())
-    }
-    
-    /// Reposition the player in some direction.
-    /// The player's velocity is an inherent characteristic, however the time since
-    /// the last tick must be taken into account to compute distance.
-    pub fn reposition(&mut self, action: Action, time: Duration) {
+    /// Shoot `weapon` instead while focused.
+    pub fn with_focused_weapon(mut self, weapon: Weapon) -> Self {
+        self.focused_weapon = Some(weapon);
+        self
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn is_firing(&self) -> bool {
+        self.firing
+    }
+
+    pub fn power(&self) -> u32 {
+        self.power
+    }
+
+    /// How powerful the player's weapons are, from 0 up to `MAX_LEVEL`.
+    pub fn level(&self) -> u32 {
+        (self.power / POWER_PER_LEVEL).min(MAX_LEVEL)
+    }
+
+    /// Collect `amount` power, swapping in the player's weapons for the new level
+    /// on going up one.
+    pub fn power_up(&mut self, amount: u32) {
+        let level = self.level();
+        self.power = (self.power + amount).min(POWER_PER_LEVEL * MAX_LEVEL);
+
+        if self.level() != level {
+            self.equip(weapon(self.level()));
+            self.equip_focused(focused_weapon(self.level()));
+        }
+    }
+
+    /// Start or stop moving in the direction an action refers to.
+    pub fn apply_action(&mut self, action: Action) {
         match action {
             Action::Move(dir) => {
                 if dir.is_horizontal() {
@@ -99,77 +142,193 @@ impl Player {
                     self.vertical_direction = Direction::Stationary;
                 }
             },
-            _ => {},
+            Action::Focus => self.focused = true,
+            Action::Unfocus => self.focused = false,
+            Action::StartFiring => self.firing = true,
+            Action::StopFiring => self.firing = false,
+            Action::Bomb => {},
         }
+    }
 
-        let dx = self.horizontal_velocity(time).distance(time).0;
-        let dy = self.vertical_velocity(time).distance(time).0;
+    /// The weapon the player shoots right now, which depends on whether they're focused.
+    pub fn weapon(&self) -> &Weapon {
+        match self.focused_weapon.as_ref() {
+            Some(weapon) if self.focused => weapon,
+            _ => &self.weapon,
+        }
+    }
 
-        self.position = Position::new(
-            units::Pixels(self.position.x.value() + dx),
-            units::Pixels(self.position.y.value() + dy),
-        );
+    /// Swap the weapon the player shoots.
+    pub fn equip(&mut self, weapon: Weapon) {
+        self.weapon = weapon;
     }
 
-    pub fn hitbox_rect(&self) -> graphics::Rect {
-        graphics::Rect::new(
-            self.position.x.value(),
-            self.position.y.value(),
-            self.dimensions.width.value(),
-            self.dimensions.height.value(),
-        )
+    /// Swap the weapon the player shoots while focused.
+    pub fn equip_focused(&mut self, weapon: Weapon) {
+        self.focused_weapon = Some(weapon);
     }
-}
 
-impl Health for Player {
-    fn health(&self) -> HealthPoints {
-        self.health
+    /// Fire a volley from the player's current weapon at `position` straight away.
+    pub fn shoot(&mut self, position: Position<units::Pixels>, target: Option<Target>, rng: &mut Rng) -> Vec<bullets::Bullet> {
+        let level = self.level();
+
+        self.weapon_mut().fire(position, target, rng)
+            .into_iter()
+            .map(|bullet| bullet.with_level(level))
+            .collect()
+    }
+
+    /// Let the player's weapons cool down for `time`, firing the current one from
+    /// `position` if the trigger is held and it's ready.
+    pub fn update_weapons(
+        &mut self,
+        time: Duration,
+        position: Position<units::Pixels>,
+        target: Option<Target>,
+        rng: &mut Rng,
+    ) -> Vec<bullets::Bullet> {
+        let (firing, focused, level) = (self.firing, self.focused, self.level());
+
+        let (current, other) = match self.focused_weapon.as_mut() {
+            Some(focused_weapon) if focused => (focused_weapon, Some(&mut self.weapon)),
+            Some(focused_weapon) => (&mut self.weapon, Some(focused_weapon)),
+            None => (&mut self.weapon, None),
+        };
+
+        if let Some(other) = other {
+            other.update(time, false, position, target, rng);
+        }
+
+        current.update(time, firing, position, target, rng)
+            .into_iter()
+            .map(|bullet| bullet.with_level(level))
+            .collect()
     }
 
-    fn restore_health(&mut self, amount: HealthPoints) -> HealthPoints {
-        self.health = self.health - amount;
-        self.health
+    fn weapon_mut(&mut self) -> &mut Weapon {
+        match self.focused_weapon.as_mut() {
+            Some(weapon) if self.focused => weapon,
+            _ => &mut self.weapon,
+        }
     }
 
-    fn take_damage(&mut self, amount: HealthPoints) -> HealthPoints {
-        self.health = self.health - amount;
-        self.health
+    /// The player's velocity is an inherent characteristic, so it only depends on
+    /// the directions the player is moving in and whether they're focused.
+    pub fn velocity(&self) -> Velocity<units::PixelsPerMs> {
+        let speed = if self.focused { FOCUSED_SPEED } else { SPEED };
+
+        let x = match self.horizontal_direction {
+            Direction::Right => units::PixelsPerMs(speed),
+            Direction::Left  => units::PixelsPerMs(-speed),
+            _                => units::PixelsPerMs(0.0),
+        };
+
+        let y = match self.vertical_direction {
+            Direction::Down => units::PixelsPerMs(speed),
+            Direction::Up   => units::PixelsPerMs(-speed),
+            _               => units::PixelsPerMs(0.0),
+        };
+
+        Velocity::new(x, y)
     }
 }
 
-impl Acceleration<units::PixelsPerMs> for Player {
-    fn horizontal_velocity(&self, time: Duration) -> Velocity<units::PixelsPerMs> {
-        match self.horizontal_direction {
-            Direction::Right => Velocity::new(0.5),
-            Direction::Left  => Velocity::new(-0.5),
-            _                => Velocity::new(0.0),
+impl Player {
+    pub fn new(ui: &UI) -> Self {
+        let position = Position::new(
+            units::Pixels(ui.width / 2.0 - 12.0),
+            units::Pixels(ui.height - 64.0)
+        );
+
+        Player {
+            transform: Transform::new(
+                position,
+                Dimensions::new(
+                    units::Pixels(24.0),
+                    units::Pixels(32.0)
+                ),
+            ),
+            hitbox: Hitbox(collision::Shape::Circle { centre: (12.0, 16.0), radius: 4.0 }),
+            pilot: Pilot::new(weapon(0)).with_focused_weapon(focused_weapon(0)),
+            lives: Lives::new(LIVES, position),
+            bombs: Bombs::new(BOMBS, MAX_BOMBS),
+            health: HealthPoints::new(250),
         }
     }
-    
-    fn vertical_velocity(&self, time: Duration) -> Velocity<units::PixelsPerMs> {
-        match self.vertical_direction {
-            Direction::Down => Velocity::new(0.5),
-            Direction::Up   => Velocity::new(-0.5),
-            _               => Velocity::new(0.0),
-        }
+
+    /// Only let bullets hit the part of the player inside `hitbox`, relative to its position.
+    pub fn with_hitbox(mut self, hitbox: collision::Shape) -> Self {
+        self.hitbox = Hitbox(hitbox);
+        self
     }
+
 }
 
-impl Action {
-    pub fn from_key_code(key_code: KeyCode, key: KeyPress) -> Option<Self> {
-        let action = if key == KeyPress::Released {
-            Action::StopMoving
-        } else {
-            Action::Move
-        };
+impl Bundle for Player {
+    fn insert(self, world: &mut World, entity: Entity) {
+        world.transforms.insert(entity, self.transform);
+        world.velocities.insert(entity, components::Velocity(Kinematics::new(self.pilot.velocity())));
+        world.hitboxes.insert(entity, self.hitbox);
+        world.healths.insert(entity, self.health);
+        world.factions.insert(entity, Faction::Player);
+        world.renderables.insert(entity, Renderable::new((255, 0, 0), Layer::Player));
+        world.pilots.insert(entity, self.pilot);
+        world.lives.insert(entity, self.lives);
+        world.bombs.insert(entity, self.bombs);
+        world.trails.insert(entity, Trail::new(MAX_LEVEL as usize));
+    }
+}
 
-        match key_code {
-            KeyCode::Up    => Some(action(Direction::Up)),
-            KeyCode::Down  => Some(action(Direction::Down)),
-            KeyCode::Left  => Some(action(Direction::Left)),
-            KeyCode::Right => Some(action(Direction::Right)),
-            KeyCode::Space => Some(Action::Shoot),
-            _              => None,
-        }
+/// The player's usual weapon at `level`: three parallel streams from the nose and
+/// wings, with the nose fanning out and more streams of homing shots angling off
+/// to the sides as the level goes up.
+fn weapon(level: u32) -> Weapon {
+    let nose = 1 + if level >= 2 { 2 } else { 0 };
+    let mut weapon = Weapon::new(Duration::milliseconds(100 - 5 * level as i64))
+        .with_stream(forwards(Pattern::Spread { count: nose, arc: units::Degrees(10.0).into() }, 2.0), muzzle(12.0, 0.0))
+        .with_stream(forwards(Pattern::Spread { count: 1, arc: units::Radians(0.0) }, 2.0), muzzle(4.0, 10.0))
+        .with_stream(forwards(Pattern::Spread { count: 1, arc: units::Radians(0.0) }, 2.0), muzzle(20.0, 10.0));
+
+    let angles: &[f32] = match level {
+        0     => &[],
+        1 | 2 => &[10.0],
+        _     => &[10.0, 20.0],
+    };
+
+    for &angle in angles {
+        weapon = weapon
+            .with_stream(seeking(-angle, 2.0), muzzle(4.0, 10.0))
+            .with_stream(seeking(angle, 2.0), muzzle(20.0, 10.0));
     }
+
+    weapon
+}
+
+/// The player's weapon while focused at `level`: one tight fan from the nose,
+/// with more bullets in it as the level goes up.
+fn focused_weapon(level: u32) -> Weapon {
+    Weapon::new(Duration::milliseconds(80))
+        .with_stream(forwards(Pattern::Spread { count: 3 + level, arc: units::Degrees(6.0).into() }, 2.5), muzzle(12.0, 0.0))
+}
+
+/// An emitter of the player's firing `pattern` straight up the screen at `speed` pixels per millisecond.
+fn forwards(pattern: Pattern, speed: f32) -> Emitter {
+    Emitter::new(Faction::Player, bullets::Kind::Basic, pattern, units::PixelsPerMs(speed), Duration::zero())
+        .with_heading(units::Degrees(-90.0))
+}
+
+/// An emitter of single homing shots, setting off `degrees` clockwise from straight
+/// up the screen before turning towards the nearest enemy.
+fn seeking(degrees: f32, speed: f32) -> Emitter {
+    Emitter::new(Faction::Player, bullets::Kind::Homing, Pattern::Spread { count: 1, arc: units::Radians(0.0) }, units::PixelsPerMs(speed), Duration::zero())
+        .with_heading(units::Degrees(degrees - 90.0))
+}
+
+fn muzzle(x: f32, y: f32) -> Offset<units::Pixels> {
+    Offset::new(units::Pixels(x), units::Pixels(y))
+}
+
+/// The player as something to aim at, aiming for their middle.
+pub fn as_target(transform: &Transform, pilot: &Pilot) -> Target {
+    Target::new(transform.centre(), pilot.velocity())
 }
@@ -0,0 +1,208 @@
+use crate::{
+    config::ui::UI,
+    game::world::{Entity, World},
+    physics::{
+        collision,
+        units,
+    },
+};
+
+use ggez::{
+    graphics::{
+        self,
+        Color,
+    },
+    Context,
+    GameResult,
+};
+
+
+/// Draw a filled rectangle covering `rect`.
+pub fn draw_rect(ctx: &mut Context, rect: collision::Rect, color: Color) -> GameResult {
+    let mesh = graphics::Mesh::new_rectangle(
+        ctx,
+        graphics::DrawMode::fill(),
+        graphics::Rect::new(rect.x, rect.y, rect.w, rect.h),
+        color,
+    )?;
+
+    graphics::draw(ctx, &mesh, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+    Ok(())
+}
+
+/// Draw everything renderable `alpha` of the way between its last two ticks, a
+/// layer at a time. Players who are dead or blinking out are left out, along with
+/// their drones.
+pub fn draw_entities(ctx: &mut Context, world: &World, alpha: f32) -> GameResult {
+    let visible = |entity| world.lives.get(entity).is_none_or(|lives| lives.is_visible());
+
+    let mut drawn: Vec<_> = world.renderables.iter()
+        .filter(|&(entity, _)| visible(world.satellites.get(entity).map_or(entity, |satellite| satellite.owner())))
+        .collect();
+    drawn.sort_by_key(|(_, renderable)| renderable.layer);
+
+    for (entity, renderable) in drawn {
+        if let Some(transform) = world.transforms.get(entity) {
+            let (r, g, b) = renderable.colour;
+
+            draw_rect(ctx, transform.interpolated_rect(alpha), Color::from_rgb(r, g, b))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draw a growing, fading burst where any player who has just died was, `alpha` of
+/// the way between their last two ticks.
+pub fn draw_explosions(ctx: &mut Context, world: &World, alpha: f32) -> GameResult {
+    const RADIUS: f32 = 48.0;
+
+    for (entity, lives) in world.lives.iter() {
+        let progress = match lives.explosion() {
+            Some(progress) => progress,
+            None => continue,
+        };
+
+        if let Some(transform) = world.transforms.get(entity) {
+            let centre = transform.interpolated_rect(alpha).centre();
+            let colour = Color::new(1.0, 0.5, 0.0, 1.0 - progress);
+
+            draw_circle(ctx, centre, (RADIUS * progress.sqrt()).max(1.0), colour)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draw a filled circle of `radius` around `centre`.
+pub fn draw_circle(ctx: &mut Context, centre: collision::Point, radius: f32, color: Color) -> GameResult {
+    let (x, y) = centre;
+    let mesh = graphics::Mesh::new_circle(
+        ctx,
+        graphics::DrawMode::fill(),
+        ggez::mint::Point2 { x, y },
+        radius,
+        0.1,
+        color,
+    )?;
+
+    graphics::draw(ctx, &mesh, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+    Ok(())
+}
+
+/// Draw `shape`. Circles and rectangles are drawn exactly; anything else is drawn
+/// as the rectangle around it.
+pub fn draw_shape(ctx: &mut Context, shape: &collision::Shape, color: Color) -> GameResult {
+    match *shape {
+        collision::Shape::Circle { centre, radius } => draw_circle(ctx, centre, radius, color),
+        _ => draw_rect(ctx, shape.bounds(), color),
+    }
+}
+
+/// Draw the real hitbox of any player holding focus over the top of everything else,
+/// `alpha` of the way between their last two ticks.
+pub fn draw_focused_hitboxes(ctx: &mut Context, world: &World, alpha: f32) -> GameResult {
+    for (entity, pilot) in world.pilots.iter() {
+        if !pilot.is_focused() {
+            continue;
+        }
+
+        if let (Some(transform), Some(hitbox)) = (world.transforms.get(entity), world.hitboxes.get(entity)) {
+            draw_shape(ctx, &hitbox.at(transform.interpolated_position(alpha)), Color::WHITE)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draw a boss's health bar across the top of the screen, with a pip for each phase
+/// it has left and, if the phase has a time limit, how much of it remains.
+pub fn draw_boss_health(ctx: &mut Context, world: &World, boss: Entity, ui: &UI) -> GameResult {
+    const MARGIN: f32 = 8.0;
+    const HEIGHT: f32 = 8.0;
+    const PIP: f32 = 6.0;
+
+    let (phases, health) = match (world.bosses.get(boss), world.health(boss)) {
+        (Some(phases), Some(health)) => (phases, health),
+        _ => return Ok(()),
+    };
+
+    let width = ui.width - MARGIN * 2.0;
+    let remaining = health.current() as f32 / health.maximum().max(1) as f32;
+
+    draw_rect(ctx, collision::Rect::new(MARGIN, MARGIN, width, HEIGHT), Color::from_rgb(64, 64, 64))?;
+    draw_rect(ctx, collision::Rect::new(MARGIN, MARGIN, width * remaining, HEIGHT), Color::RED)?;
+
+    for pip in 0..phases.remaining() {
+        let x = MARGIN + pip as f32 * (PIP + 2.0);
+
+        draw_rect(ctx, collision::Rect::new(x, MARGIN + HEIGHT + 2.0, PIP, PIP), Color::RED)?;
+    }
+
+    let time_left = phases.time_left().zip(phases.current().duration);
+    if let Some((left, total)) = time_left {
+        let fraction = units::milliseconds(left) / units::milliseconds(total).max(1.0);
+        let bar = collision::Rect::new(MARGIN, MARGIN + HEIGHT + PIP + 4.0, width * fraction, 2.0);
+
+        draw_rect(ctx, bar, Color::BLACK)?;
+    }
+
+    Ok(())
+}
+
+/// Draw how many lives and bombs `player` has left and their weapon level in the
+/// bottom corner of the screen, a pip for each, under a bar of their health.
+pub fn draw_hud(ctx: &mut Context, world: &World, player: Entity, ui: &UI) -> GameResult {
+    const MARGIN: f32 = 8.0;
+    const PIP: f32 = 8.0;
+    const BAR: f32 = 100.0;
+
+    let counts = [
+        (world.lives.get(player).map_or(0, |lives| lives.remaining()), Color::RED),
+        (world.bombs.get(player).map_or(0, |bombs| bombs.stock()), Color::GREEN),
+        (world.pilots.get(player).map_or(0, |pilot| pilot.level()), Color::BLUE),
+    ];
+
+    for (row, &(count, color)) in counts.iter().enumerate() {
+        let y = ui.height - MARGIN - PIP - row as f32 * (PIP + 4.0);
+
+        for pip in 0..count {
+            let x = MARGIN + pip as f32 * (PIP + 2.0);
+
+            draw_rect(ctx, collision::Rect::new(x, y, PIP, PIP), color)?;
+        }
+    }
+
+    if let Some(health) = world.health(player) {
+        let y = ui.height - MARGIN - PIP - counts.len() as f32 * (PIP + 4.0);
+        let remaining = health.current() as f32 / health.maximum().max(1) as f32;
+
+        draw_rect(ctx, collision::Rect::new(MARGIN, y, BAR, PIP / 2.0), Color::from_rgb(64, 64, 64))?;
+        draw_rect(ctx, collision::Rect::new(MARGIN, y, BAR * remaining, PIP / 2.0), Color::RED)?;
+    }
+
+    Ok(())
+}
+
+/// Darken the screen and write "GAME OVER" across the middle of it.
+pub fn draw_game_over(ctx: &mut Context, ui: &UI) -> GameResult {
+    draw_banner(ctx, ui, "GAME OVER")
+}
+
+/// Darken the screen and announce that the stage called `name` is clear.
+pub fn draw_stage_clear(ctx: &mut Context, ui: &UI, name: &str) -> GameResult {
+    draw_banner(ctx, ui, &format!("STAGE CLEAR\n{}", name))
+}
+
+/// Darken the screen and write `message` across the middle of it.
+fn draw_banner(ctx: &mut Context, ui: &UI, message: &str) -> GameResult {
+    draw_rect(ctx, collision::Rect::new(0.0, 0.0, ui.width, ui.height), Color::new(0.0, 0.0, 0.0, 0.6))?;
+
+    let text = graphics::Text::new(graphics::TextFragment::new(message).scale(48.0).color(Color::WHITE));
+    let size = text.dimensions(ctx);
+    let corner = ggez::mint::Point2 { x: (ui.width - size.w) / 2.0, y: (ui.height - size.h) / 2.0 };
+
+    graphics::draw(ctx, &text, (corner,))
+}
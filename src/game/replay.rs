@@ -0,0 +1,429 @@
+use crate::{
+    config::ui::UI,
+    game::{
+        player::Action,
+        stage::{Stage, StageError},
+        trace::Fingerprint,
+    },
+    physics::motion::Direction,
+};
+
+use std::{
+    collections::BTreeMap,
+    error,
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+
+/// The version of the replay file format written by this build. It goes up
+/// whenever the header or the set of actions changes.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// The version of the game that recorded a replay.
+/// Replays are only guaranteed to play back identically on the same version.
+pub const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const MAGIC: &str = "glhf-replay";
+
+/// Everything needed to reconstruct the simulation a replay was recorded against.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Header {
+    pub format_version: u32,
+    pub game_version: String,
+    pub seed: u64,
+    pub width: f32,
+    pub height: f32,
+    /// The stage file the game was played on, or `None` for the default stage.
+    pub stage: Option<String>,
+    /// A hash of the stage file as it was when the game was played, to tell if
+    /// it has changed since.
+    pub stage_hash: Option<u64>,
+}
+
+/// The actions the player took on each tick of a game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub header: Header,
+
+    length: u64,
+    actions: BTreeMap<u64, Vec<Action>>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Stage(StageError),
+    Parse { line: usize, message: String },
+    Mismatch { field: &'static str, expected: String, found: String },
+}
+
+/// Records the actions fed to each tick and writes them out as a replay.
+pub struct Recorder {
+    replay: Replay,
+    path: PathBuf,
+}
+
+/// Feeds a replay's actions back into the simulation tick by tick.
+pub struct Playback {
+    replay: Replay,
+}
+
+impl Header {
+    pub fn new(seed: u64, ui: &UI) -> Self {
+        Header {
+            format_version: FORMAT_VERSION,
+            game_version: GAME_VERSION.to_string(),
+            seed,
+            width: ui.width,
+            height: ui.height,
+            stage: None,
+            stage_hash: None,
+        }
+    }
+
+    /// Play on the stage file at `path`, noting what it contains right now.
+    pub fn with_stage<S: Into<String>>(mut self, path: S) -> Result<Self, ReplayError> {
+        let path = path.into();
+        let source = fs::read_to_string(&path).map_err(|err| ReplayError::Stage(StageError::Io(err)))?;
+
+        self.stage_hash = Some(stage_hash(&source));
+        self.stage = Some(path);
+        Ok(self)
+    }
+
+    /// Load the stage the replay was recorded on, as long as it hasn't changed since.
+    pub fn load_stage(&self) -> Result<Stage, ReplayError> {
+        let path = match self.stage.as_ref() {
+            Some(path) => path,
+            None => return Ok(Stage::default()),
+        };
+
+        let source = fs::read_to_string(path).map_err(|err| ReplayError::Stage(StageError::Io(err)))?;
+        let hash = stage_hash(&source);
+        if let Some(expected) = self.stage_hash.filter(|&expected| expected != hash) {
+            return Err(ReplayError::mismatch("stage hash", format!("{:016x}", hash), format!("{:016x}", expected)));
+        }
+
+        Stage::parse(&source).map_err(ReplayError::Stage)
+    }
+
+    /// Check that the replay was recorded by this version of the game with the same config.
+    pub fn verify(&self, ui: &UI) -> Result<(), ReplayError> {
+        if self.game_version != GAME_VERSION {
+            return Err(ReplayError::mismatch("game version", GAME_VERSION, &self.game_version));
+        }
+
+        if self.width != ui.width || self.height != ui.height {
+            return Err(ReplayError::mismatch(
+                "ui dimensions",
+                format!("{}x{}", ui.width, ui.height),
+                format!("{}x{}", self.width, self.height),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Replay {
+    pub fn new(header: Header) -> Self {
+        Replay {
+            header,
+            length: 0u64,
+            actions: BTreeMap::new(),
+        }
+    }
+
+    /// The number of ticks the replay covers.
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Record that `actions` were applied on `tick`.
+    pub fn record(&mut self, tick: u64, actions: &[Action]) {
+        if !actions.is_empty() {
+            self.actions.entry(tick).or_default().extend_from_slice(actions);
+        }
+
+        self.length = self.length.max(tick + 1);
+    }
+
+    pub fn actions_at(&self, tick: u64) -> &[Action] {
+        self.actions.get(&tick).map(|actions| actions.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ReplayError> {
+        let contents = fs::read_to_string(path).map_err(ReplayError::Io)?;
+
+        Replay::parse(&contents)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ReplayError> {
+        fs::write(path, self.to_string()).map_err(ReplayError::Io)
+    }
+
+    pub fn parse<'a>(contents: &'a str) -> Result<Self, ReplayError> {
+        let mut lines = contents
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .peekable();
+
+        let field = |lines: &mut dyn Iterator<Item = (usize, &'a str)>, name: &str| {
+            let (line_number, line) = lines.next().ok_or_else(|| ReplayError::Parse {
+                line: 0,
+                message: format!("missing `{}` header", name),
+            })?;
+
+            let mut words = line.split_whitespace();
+            if words.next() != Some(name) {
+                return Err(ReplayError::parse(line_number, format!("expected `{}` header", name)));
+            }
+
+            Ok((line_number, words.collect::<Vec<&str>>()))
+        };
+
+        let (line, words) = field(&mut lines, MAGIC)?;
+        let format_version: u32 = parse_word(line, words.first())?;
+        if format_version != FORMAT_VERSION {
+            return Err(ReplayError::mismatch(
+                "format version",
+                FORMAT_VERSION.to_string(),
+                format_version.to_string(),
+            ));
+        }
+
+        let (line, words) = field(&mut lines, "game-version")?;
+        let game_version = words.first()
+            .ok_or_else(|| ReplayError::parse(line, "missing game version"))?
+            .to_string();
+
+        let (line, words) = field(&mut lines, "seed")?;
+        let seed: u64 = parse_word(line, words.first())?;
+
+        let (line, words) = field(&mut lines, "ui")?;
+        let width: f32 = parse_word(line, words.first())?;
+        let height: f32 = parse_word(line, words.get(1))?;
+
+        // Replays of the default stage don't name one.
+        let (stage, stage_hash) = match lines.peek() {
+            Some((_, line)) if line.starts_with("stage ") => {
+                let (_, words) = field(&mut lines, "stage")?;
+                let (line, hash) = field(&mut lines, "stage-hash")?;
+                let hash = hash.first()
+                    .and_then(|hash| u64::from_str_radix(hash, 16).ok())
+                    .ok_or_else(|| ReplayError::parse(line, "invalid stage hash"))?;
+
+                (Some(words.join(" ")), Some(hash))
+            },
+            _ => (None, None),
+        };
+
+        let (line, words) = field(&mut lines, "length")?;
+        let length: u64 = parse_word(line, words.first())?;
+
+        let mut replay = Replay::new(Header {
+            format_version,
+            game_version,
+            seed,
+            width,
+            height,
+            stage,
+            stage_hash,
+        });
+
+        for (line, text) in lines {
+            let mut words = text.split_whitespace();
+            if words.next() != Some("tick") {
+                return Err(ReplayError::parse(line, "expected `tick` line"));
+            }
+
+            let words: Vec<&str> = words.collect();
+            let tick: u64 = parse_word(line, words.first())?;
+            if tick >= length {
+                return Err(ReplayError::parse(line, format!("tick {} is past the end of the replay", tick)));
+            }
+
+            let actions = words[1..]
+                .iter()
+                .map(|word| decode_action(word).ok_or_else(|| {
+                    ReplayError::parse(line, format!("unknown action `{}`", word))
+                }))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            replay.record(tick, &actions);
+        }
+
+        replay.length = length;
+
+        Ok(replay)
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} {}", MAGIC, self.header.format_version)?;
+        writeln!(f, "game-version {}", self.header.game_version)?;
+        writeln!(f, "seed {}", self.header.seed)?;
+        writeln!(f, "ui {} {}", self.header.width, self.header.height)?;
+        if let Some(stage) = self.header.stage.as_ref() {
+            writeln!(f, "stage {}", stage)?;
+            writeln!(f, "stage-hash {:016x}", self.header.stage_hash.unwrap_or_default())?;
+        }
+        writeln!(f, "length {}", self.length)?;
+
+        for (tick, actions) in self.actions.iter() {
+            write!(f, "tick {}", tick)?;
+
+            for action in actions.iter() {
+                write!(f, " {}", encode_action(*action))?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ReplayError {
+    fn parse<S: Into<String>>(line: usize, message: S) -> Self {
+        ReplayError::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+
+    fn mismatch<E: ToString, F: ToString>(field: &'static str, expected: E, found: F) -> Self {
+        ReplayError::Mismatch {
+            field,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "could not access replay: {}", err),
+            ReplayError::Stage(err) => write!(f, "could not load the replay's stage: {}", err),
+            ReplayError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            ReplayError::Mismatch { field, expected, found } => {
+                write!(f, "replay {} is {} but this game has {}", field, found, expected)
+            },
+        }
+    }
+}
+
+impl error::Error for ReplayError {}
+
+impl Recorder {
+    pub fn new<P: Into<PathBuf>>(header: Header, path: P) -> Self {
+        Recorder {
+            replay: Replay::new(header),
+            path: path.into(),
+        }
+    }
+
+    pub fn record(&mut self, tick: u64, actions: &[Action]) {
+        self.replay.record(tick, actions);
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn save(&self) -> Result<(), ReplayError> {
+        self.replay.save(&self.path)
+    }
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Playback {
+            replay,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The actions to apply on `tick`, or `None` once the replay has run out.
+    pub fn actions_at(&self, tick: u64) -> Option<&[Action]> {
+        if tick < self.replay.length() {
+            Some(self.replay.actions_at(tick))
+        } else {
+            None
+        }
+    }
+}
+
+/// A hash of a stage file's contents.
+fn stage_hash(source: &str) -> u64 {
+    Fingerprint::new().bytes(source.as_bytes()).finish()
+}
+
+fn parse_word<T: std::str::FromStr>(line: usize, word: Option<&&str>) -> Result<T, ReplayError> {
+    let word = word.ok_or_else(|| ReplayError::parse(line, "missing value"))?;
+
+    word.parse().map_err(|_| ReplayError::parse(line, format!("invalid value `{}`", word)))
+}
+
+fn encode_action(action: Action) -> String {
+    match action {
+        Action::Move(dir)       => format!("move-{}", encode_direction(dir)),
+        Action::StopMoving(dir) => format!("stop-{}", encode_direction(dir)),
+        Action::Focus           => "focus".to_string(),
+        Action::Unfocus         => "unfocus".to_string(),
+        Action::StartFiring     => "start-firing".to_string(),
+        Action::StopFiring      => "stop-firing".to_string(),
+        Action::Bomb            => "bomb".to_string(),
+    }
+}
+
+fn decode_action(word: &str) -> Option<Action> {
+    if word == "focus" {
+        Some(Action::Focus)
+    } else if word == "unfocus" {
+        Some(Action::Unfocus)
+    } else if word == "start-firing" {
+        Some(Action::StartFiring)
+    } else if word == "stop-firing" {
+        Some(Action::StopFiring)
+    } else if word == "bomb" {
+        Some(Action::Bomb)
+    } else if let Some(dir) = word.strip_prefix("move-") {
+        decode_direction(dir).map(Action::Move)
+    } else if let Some(dir) = word.strip_prefix("stop-") {
+        decode_direction(dir).map(Action::StopMoving)
+    } else {
+        None
+    }
+}
+
+fn encode_direction(dir: Direction) -> &'static str {
+    match dir {
+        Direction::Up         => "up",
+        Direction::Down       => "down",
+        Direction::Left       => "left",
+        Direction::Right      => "right",
+        Direction::Stationary => "stationary",
+    }
+}
+
+fn decode_direction(word: &str) -> Option<Direction> {
+    match word {
+        "up"         => Some(Direction::Up),
+        "down"       => Some(Direction::Down),
+        "left"       => Some(Direction::Left),
+        "right"      => Some(Direction::Right),
+        "stationary" => Some(Direction::Stationary),
+        _            => None,
+    }
+}
@@ -0,0 +1,414 @@
+//! A small declarative language for describing danmaku patterns, in the spirit of
+//! BulletML but written in RON.
+//!
+//! A script is a list of actions run one tick at a time by a [`Runner`] attached to
+//! an enemy or a bullet. For example, a flower that fires eight rings of slowing
+//! bullets, each ring turned a little further than the last:
+//!
+//! ```ron
+//! [
+//!     Repeat(times: 8, actions: [
+//!         Repeat(times: 12, actions: [
+//!             Fire(heading: Sequence(30.0), speed: Absolute(0.4), bullet: [
+//!                 ChangeSpeed(speed: Absolute(0.1), over: 30),
+//!             ]),
+//!         ]),
+//!         Fire(heading: Sequence(7.5), speed: Absolute(0.4)),
+//!         Wait(10),
+//!     ]),
+//! ]
+//! ```
+//!
+//! Angles are in degrees clockwise from the positive x axis, speeds in pixels per
+//! millisecond and durations in simulation ticks.
+
+use crate::{
+    game::{
+        bullets,
+        components::Faction,
+    },
+    physics::{
+        motion::{
+            Kinematics,
+            Offset,
+            Position,
+            Velocity,
+        },
+        units::{
+            Degrees,
+            Pixels,
+            PixelsPerMs,
+            Radians,
+        },
+    },
+};
+
+use std::{
+    error,
+    f32::consts::{PI, TAU},
+    fmt,
+    fs,
+    io,
+    path::Path,
+    rc::Rc,
+};
+
+use serde::Deserialize;
+
+
+/// The most actions a runner will execute in a single tick, so that a loop
+/// without a `Wait` in it cannot hang the game.
+const MAX_ACTIONS_PER_TICK: u32 = 1024;
+
+/// A list of actions, run in order.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Script(pub Rc<Vec<Action>>);
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub enum Action {
+    /// Fire a bullet, which runs its own `bullet` script once fired.
+    Fire {
+        heading: Heading,
+        speed: Speed,
+        #[serde(default)]
+        bullet: Option<Script>,
+    },
+    /// Run `actions` `times` times over.
+    Repeat { times: u32, actions: Script },
+    /// Run `actions` over and over until the host is gone.
+    Loop(Script),
+    /// Do nothing for some number of ticks.
+    Wait(u32),
+    /// Turn the host to a new heading over some number of ticks.
+    ChangeDirection { heading: Heading, over: u32 },
+    /// Speed the host up or slow it down over some number of ticks.
+    ChangeSpeed { speed: Speed, over: u32 },
+    /// Remove the host from the game.
+    Vanish,
+}
+
+/// Which way to fire or turn, in degrees.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum Heading {
+    /// Clockwise from the positive x axis, i.e. 90.0 is straight down.
+    Absolute(f32),
+    /// Relative to the direction of the player.
+    Aim(f32),
+    /// Relative to the direction the host is moving in.
+    Relative(f32),
+    /// Relative to the previous bullet this script fired.
+    Sequence(f32),
+}
+
+/// How fast to fire or move, in pixels per millisecond.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum Speed {
+    Absolute(f32),
+    /// Relative to the speed the host is moving at.
+    Relative(f32),
+    /// Relative to the previous bullet this script fired.
+    Sequence(f32),
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(io::Error),
+    Syntax { line: usize, column: usize, message: String },
+}
+
+/// What a script is running on: where it fires from and how it moves.
+pub struct Host<'a> {
+    /// Where the bullets it fires are centred.
+    pub position: Position<Pixels>,
+    pub kinematics: &'a mut Kinematics,
+    pub faction: Faction,
+    /// Where the player is, for aimed shots.
+    pub target: Option<Position<Pixels>>,
+}
+
+/// Runs a script one tick at a time.
+#[derive(Clone, Debug)]
+pub struct Runner {
+    stack: Vec<Frame>,
+    wait: u32,
+    vanished: bool,
+
+    /// The way the host last moved, remembered for when it comes to a stop.
+    heading: Radians,
+    last_heading: Radians,
+    last_speed: PixelsPerMs,
+    heading_change: Option<Transition<Radians>>,
+    speed_change: Option<Transition<PixelsPerMs>>,
+}
+
+/// A list of actions part way through being run.
+#[derive(Clone, Debug)]
+struct Frame {
+    actions: Rc<Vec<Action>>,
+    index: usize,
+    /// How many more times to run the list once it finishes, or `None` to run forever.
+    remaining: Option<u32>,
+}
+
+/// A value moving linearly from one setting to another over a number of ticks.
+#[derive(Copy, Clone, Debug)]
+struct Transition<T> {
+    from: T,
+    to: T,
+    elapsed: u32,
+    duration: u32,
+}
+
+impl Script {
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_str(source)
+            .map_err(|err| ScriptError::Syntax {
+                line: err.position.line,
+                column: err.position.col,
+                message: err.code.to_string(),
+            })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ScriptError> {
+        Script::parse(&fs::read_to_string(path).map_err(ScriptError::Io)?)
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.0
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptError::Io(err) => write!(f, "could not read script: {}", err),
+            ScriptError::Syntax { line, column, message } => {
+                write!(f, "{}:{}: {}", line, column, message)
+            },
+        }
+    }
+}
+
+impl error::Error for ScriptError {}
+
+impl Runner {
+    pub fn new(script: &Script) -> Self {
+        Runner {
+            stack: vec![Frame { actions: script.0.clone(), index: 0, remaining: Some(1) }],
+            wait: 0,
+            vanished: false,
+            heading: Radians(PI / 2.0),
+            last_heading: Radians(PI / 2.0),
+            last_speed: PixelsPerMs(0.0),
+            heading_change: None,
+            speed_change: None,
+        }
+    }
+
+    /// Whether the script has nothing left to do.
+    pub fn finished(&self) -> bool {
+        self.stack.is_empty() && self.heading_change.is_none() && self.speed_change.is_none()
+    }
+
+    /// Whether the script asked for its host to be removed.
+    pub fn vanished(&self) -> bool {
+        self.vanished
+    }
+
+    /// Run one tick of the script against `host`, returning any bullets it fired.
+    pub fn tick(&mut self, host: Host) -> Vec<bullets::Bullet> {
+        self.apply_transitions(host.kinematics);
+
+        if self.wait > 0 {
+            self.wait -= 1;
+            return vec![];
+        }
+
+        let mut fired = vec![];
+        let mut budget = MAX_ACTIONS_PER_TICK;
+
+        while budget > 0 {
+            budget -= 1;
+
+            let frame = match self.stack.last_mut() {
+                Some(frame) => frame,
+                None => break,
+            };
+
+            if frame.index >= frame.actions.len() {
+                match frame.remaining {
+                    None => frame.index = 0,
+                    Some(remaining) if remaining > 1 => {
+                        frame.remaining = Some(remaining - 1);
+                        frame.index = 0;
+                    },
+                    Some(_) => {
+                        self.stack.pop();
+                    },
+                }
+
+                continue;
+            }
+
+            let actions = frame.actions.clone();
+            let action = &actions[frame.index];
+            frame.index += 1;
+
+            match action {
+                Action::Fire { heading, speed, bullet } => {
+                    fired.push(self.fire(&host, *heading, *speed, bullet.as_ref()));
+                },
+
+                Action::Repeat { times, actions } => {
+                    if *times > 0 {
+                        self.stack.push(Frame { actions: actions.0.clone(), index: 0, remaining: Some(*times) });
+                    }
+                },
+
+                Action::Loop(actions) => {
+                    self.stack.push(Frame { actions: actions.0.clone(), index: 0, remaining: None });
+                },
+
+                Action::Wait(ticks) => {
+                    self.wait = *ticks;
+                    break;
+                },
+
+                Action::ChangeDirection { heading, over } => {
+                    let from = self.host_heading(host.kinematics);
+                    let to = from + shortest_turn(from, self.resolve_heading(&host, *heading));
+
+                    if *over == 0 {
+                        self.heading = to;
+                        host.kinematics.velocity = Velocity::from_angle(to, host.kinematics.velocity.speed());
+                    } else {
+                        self.heading_change = Some(Transition { from, to, elapsed: 0, duration: *over });
+                    }
+                },
+
+                Action::ChangeSpeed { speed, over } => {
+                    let from = host.kinematics.velocity.speed();
+                    let to = self.resolve_speed(&host, *speed);
+
+                    if *over == 0 {
+                        host.kinematics.velocity = Velocity::from_angle(self.host_heading(host.kinematics), to);
+                    } else {
+                        self.speed_change = Some(Transition { from, to, elapsed: 0, duration: *over });
+                    }
+                },
+
+                Action::Vanish => {
+                    self.vanished = true;
+                    self.stack.clear();
+                },
+            }
+        }
+
+        fired
+    }
+
+    fn fire(
+        &mut self,
+        host: &Host,
+        heading: Heading,
+        speed: Speed,
+        script: Option<&Script>,
+    ) -> bullets::Bullet {
+        let heading = self.resolve_heading(host, heading);
+        let speed = self.resolve_speed(host, speed);
+
+        self.last_heading = heading;
+        self.last_speed = speed;
+
+        let size = bullets::Kind::Basic.dimensions();
+        let corner = host.position - Offset::new(size.width / 2.0, size.height / 2.0);
+        let bullet = bullets::Bullet::new(host.faction, bullets::Kind::Basic, corner)
+            .with_kinematics(Kinematics::new(Velocity::from_angle(heading, speed)));
+
+        match script {
+            Some(script) => bullet.with_script(Runner::new(script)),
+            None => bullet,
+        }
+    }
+
+    /// The direction the host is facing, which is the way it moves unless it is standing still.
+    fn host_heading(&mut self, kinematics: &Kinematics) -> Radians {
+        if kinematics.velocity.speed().value() > 0.0 {
+            self.heading = kinematics.velocity.angle();
+        }
+
+        self.heading
+    }
+
+    fn resolve_heading(&mut self, host: &Host, heading: Heading) -> Radians {
+        match heading {
+            Heading::Absolute(degrees) => Degrees(degrees).into(),
+            Heading::Relative(degrees) => self.host_heading(host.kinematics) + Degrees(degrees).into(),
+            Heading::Sequence(degrees) => self.last_heading + Degrees(degrees).into(),
+            Heading::Aim(degrees) => {
+                let aim = host.target
+                    .map(|target| (target - host.position).angle())
+                    .unwrap_or(Radians(PI / 2.0));
+
+                aim + Degrees(degrees).into()
+            },
+        }
+    }
+
+    fn resolve_speed(&self, host: &Host, speed: Speed) -> PixelsPerMs {
+        match speed {
+            Speed::Absolute(speed) => PixelsPerMs(speed),
+            Speed::Relative(speed) => host.kinematics.velocity.speed() + PixelsPerMs(speed),
+            Speed::Sequence(speed) => self.last_speed + PixelsPerMs(speed),
+        }
+    }
+
+    fn apply_transitions(&mut self, kinematics: &mut Kinematics) {
+        if self.heading_change.is_none() && self.speed_change.is_none() {
+            return;
+        }
+
+        let mut heading = self.host_heading(kinematics);
+        let mut speed = kinematics.velocity.speed();
+
+        if let Some(change) = self.heading_change.as_mut() {
+            heading = change.advance(|from, to, t| from + (to - from) * t);
+            self.heading = heading;
+            if change.done() {
+                self.heading_change = None;
+            }
+        }
+
+        if let Some(change) = self.speed_change.as_mut() {
+            speed = change.advance(|from, to, t| from + (to - from) * t);
+            if change.done() {
+                self.speed_change = None;
+            }
+        }
+
+        kinematics.velocity = Velocity::from_angle(heading, speed);
+    }
+}
+
+impl<T: Copy> Transition<T> {
+    /// Step one tick further through the transition, returning the new value.
+    fn advance<F: Fn(T, T, f32) -> T>(&mut self, lerp: F) -> T {
+        self.elapsed = (self.elapsed + 1).min(self.duration);
+
+        lerp(self.from, self.to, self.elapsed as f32 / self.duration as f32)
+    }
+
+    fn done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// The smallest angle that turns `from` to face the same way as `to`.
+fn shortest_turn(from: Radians, to: Radians) -> Radians {
+    let turn = (to - from).value().rem_euclid(TAU);
+
+    Radians(if turn > PI { turn - TAU } else { turn })
+}
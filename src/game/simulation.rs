@@ -0,0 +1,203 @@
+use crate::{
+    config::ui::UI,
+    game::{
+        bullets,
+        components::Faction,
+        enemies,
+        patterns,
+        player,
+        rng::Rng,
+        stage::{Director, Stage},
+        systems::{self, BulletIndex, Impacts, Role},
+        timestep,
+        world::{Entity, World},
+    },
+};
+
+use chrono::Duration;
+
+
+/// How wide each cell of the bullet index is, a little over the size of a small enemy.
+const BULLET_CELL_SIZE: f32 = 64.0;
+
+/// How many bullets and enemies there is room for before the world's storage has to grow.
+const BULLET_CAPACITY: usize = 4096;
+const ENEMY_CAPACITY: usize = 256;
+
+/// The gameplay simulation, independent of any window, renderer or wall clock.
+///
+/// Each call to `tick` runs the game's `systems` over its `World` for one
+/// fixed-length step, given the set of actions the player took during it, so the
+/// same inputs always produce the same game.
+pub struct Simulation {
+    world: World,
+    player: Entity,
+    director: Director,
+    /// Where the player's bullets are, rebuilt every tick before checking for hits.
+    bullet_index: BulletIndex,
+    /// What the collision systems found, kept between ticks to save allocating it again.
+    impacts: Impacts,
+
+    score: u64,
+    tick: u64,
+    tick_length: Duration,
+    seed: u64,
+    rng: Rng,
+    ui: UI,
+}
+
+impl Simulation {
+    /// A game of the default practice stage.
+    pub fn new(ui: UI, seed: u64) -> Self {
+        Simulation::with_stage(ui, seed, Stage::default())
+    }
+
+    pub fn with_stage(ui: UI, seed: u64, stage: Stage) -> Self {
+        let mut world = World::with_capacity(BULLET_CAPACITY + ENEMY_CAPACITY);
+        let player = world.spawn(player::Player::new(&ui));
+
+        Simulation {
+            world,
+            player,
+            director: Director::new(stage),
+            bullet_index: BulletIndex::new(ui.hitbox_rect(), BULLET_CELL_SIZE),
+            impacts: Impacts::default(),
+            score: 0,
+            tick: 0u64,
+            tick_length: timestep::Timestep::default().tick_length(),
+            seed,
+            rng: Rng::new(seed),
+            ui,
+        }
+    }
+
+    /// Every entity in the game and their components.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// The player's entity, which lasts the whole game.
+    pub fn player(&self) -> Entity {
+        self.player
+    }
+
+    pub fn ui(&self) -> &UI {
+        &self.ui
+    }
+
+    /// The points earned for shooting enemies down.
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    /// The number of ticks that have been simulated so far.
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn tick_length(&self) -> Duration {
+        self.tick_length
+    }
+
+    /// The seed every random decision in this game is derived from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    pub fn add_enemy(&mut self, enemy: enemies::Enemy) -> Entity {
+        self.world.spawn(enemy)
+    }
+
+    pub fn add_bullet(&mut self, bullet: bullets::Bullet) -> Entity {
+        self.world.spawn(bullet)
+    }
+
+    pub fn director(&self) -> &Director {
+        &self.director
+    }
+
+    /// Whether every wave of the stage has arrived and been dealt with, whether
+    /// by being shot down or by leaving.
+    pub fn stage_cleared(&self) -> bool {
+        self.director.finished() && self.world.enemies().next().is_none()
+    }
+
+    /// Whether the player has run out of lives.
+    pub fn game_over(&self) -> bool {
+        self.world.lives.get(self.player).is_some_and(|lives| lives.is_game_over())
+    }
+
+    /// Advance the game by one tick, applying the player's `inputs` first.
+    pub fn tick(&mut self, inputs: &[player::Action]) {
+        let time_since_last_tick = self.tick_length;
+        let ui_rect = self.ui.hitbox_rect();
+
+        self.spawn_waves();
+        systems::apply_inputs(&mut self.world, self.player, inputs);
+        systems::bomb_timers(&mut self.world, time_since_last_tick);
+        systems::steer(&mut self.world);
+        systems::movement(&mut self.world, time_since_last_tick, Role::Player);
+        systems::confine(&mut self.world, self.player, &self.ui);
+        systems::record_trails(&mut self.world);
+        systems::sync_drones(&mut self.world, self.player);
+        systems::fly_drones(&mut self.world);
+        systems::player_fire(&mut self.world, time_since_last_tick, &mut self.rng);
+        systems::drone_fire(&mut self.world, time_since_last_tick, &mut self.rng);
+
+        // Enemy bullets only hit the player and the player's only hit enemies, so
+        // both can be checked before either lot is removed.
+        self.impacts.clear();
+        systems::index_bullets(&self.world, &mut self.bullet_index);
+        systems::player_collisions(&self.world, &mut self.impacts);
+        systems::enemy_collisions(&mut self.world, &mut self.bullet_index, &mut self.impacts);
+        systems::damage(&mut self.world, &self.impacts.hits);
+        systems::player_lives(&mut self.world, time_since_last_tick);
+
+        let phase_ended = systems::boss_phases(&mut self.world, time_since_last_tick);
+        self.score += systems::cleanup_defeated(&mut self.world, &mut self.rng);
+
+        // Enemies aim for wherever the player is before any of them move.
+        let target = self.player_target();
+        systems::movement(&mut self.world, time_since_last_tick, Role::Enemy);
+        systems::enemy_fire(&mut self.world, time_since_last_tick, target, &mut self.rng);
+        systems::cleanup_departed(&mut self.world, &ui_rect);
+
+        systems::cleanup_spent(&mut self.world, &self.impacts.spent);
+        if phase_ended {
+            systems::clear_bullets(&mut self.world, Faction::Enemy);
+        }
+
+        let target = self.world.transforms.get(self.player).map(|transform| transform.centre());
+        systems::home_in(&mut self.world, time_since_last_tick, target);
+        systems::movement(&mut self.world, time_since_last_tick, Role::Bullet);
+        systems::bullet_scripts(&mut self.world, target);
+        systems::cleanup_out_of_bounds(&mut self.world, &ui_rect);
+
+        systems::attract_items(&mut self.world, self.player, &self.ui);
+        systems::movement(&mut self.world, time_since_last_tick, Role::Item);
+        self.score += systems::collect_items(&mut self.world, self.player);
+        systems::cleanup_uncollected(&mut self.world, &ui_rect);
+
+        self.director.advance(time_since_last_tick);
+        self.tick += 1;
+    }
+
+    /// Bring in any waves of the stage that are due.
+    pub fn spawn_waves(&mut self) {
+        for enemy in self.director.spawn_due() {
+            self.world.spawn(enemy);
+        }
+    }
+
+    /// The player as something for enemies to aim at.
+    fn player_target(&self) -> Option<patterns::Target> {
+        let transform = self.world.transforms.get(self.player)?;
+        let pilot = self.world.pilots.get(self.player)?;
+
+        Some(player::as_target(transform, pilot))
+    }
+}
@@ -0,0 +1,348 @@
+//! Stages describe which enemies turn up when, written in RON.
+//!
+//! A stage is a list of waves, each spawning a group of enemies some number of
+//! milliseconds after the stage starts. Enemies are spawned by the name of their
+//! archetype, either one of the bundled ones or one the stage defines itself, and
+//! can be given weapons of their own in place of the archetype's. For example, a
+//! pair of popcorn that swoop in, strafe and leave, followed by a turret a few
+//! seconds later:
+//!
+//! ```ron
+//! Stage(
+//!     name: "Opening",
+//!     waves: [
+//!         Wave(at: 0, enemies: [
+//!             Spawn(archetype: "popcorn", position: (100, -40), path: [
+//!                 LineAt(to: (100, 120), speed: 0.3, easing: EaseOut),
+//!                 Sine(travel: (0, 300), amplitude: 60, period: 2000, duration: 4000),
+//!                 LineAt(to: (-60, 420), speed: 0.3),
+//!             ], emitters: [
+//!                 Emitter(pattern: Aimed(count: 3, arc: 30), speed: 0.3, interval: 1200),
+//!             ]),
+//!         ]),
+//!         Wave(at: 4000, enemies: [
+//!             Spawn(archetype: "turret", position: (284, 20), emitters: [], script: [Loop([
+//!                 Fire(heading: Aim(0), speed: Absolute(0.4)),
+//!                 Wait(20),
+//!             ])]),
+//!         ]),
+//!     ],
+//! )
+//! ```
+//!
+//! Positions are `(x, y)` in pixels, angles in degrees clockwise from the positive
+//! x axis, speeds in pixels per millisecond and durations in milliseconds.
+
+use crate::{
+    game::{
+        archetypes::{
+            Archetype,
+            EmitterSpec,
+            Point,
+            Registry,
+        },
+        enemies::Enemy,
+        paths::Path,
+        script::Script,
+    },
+    physics::{
+        motion::{
+            Easing,
+            Offset,
+            Position,
+        },
+        units::{
+            Pixels,
+            PixelsPerMs,
+        },
+    },
+};
+
+use std::{
+    collections::HashMap,
+    error,
+    fmt,
+    fs,
+    io,
+};
+
+use chrono::Duration;
+
+use serde::Deserialize;
+
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Stage {
+    pub name: String,
+    /// Archetypes only this stage uses, added to the bundled ones.
+    #[serde(default)]
+    pub archetypes: HashMap<String, Archetype>,
+    pub waves: Vec<Wave>,
+}
+
+/// A group of enemies that arrive together.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Wave {
+    /// When the wave arrives, in milliseconds since the start of the stage.
+    pub at: u32,
+    pub enemies: Vec<Spawn>,
+}
+
+/// One enemy in a wave.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Spawn {
+    pub archetype: String,
+    pub position: Point,
+    /// The path to follow from `position`. Enemies without one stay where they spawn.
+    /// For a boss, this replaces the movement of its first phase.
+    #[serde(default)]
+    pub path: Vec<Step>,
+    /// Fire these instead of the archetype's emitters.
+    #[serde(default)]
+    pub emitters: Option<Vec<EmitterSpec>>,
+    /// Run this instead of the archetype's script.
+    #[serde(default)]
+    pub script: Option<Script>,
+    #[serde(default)]
+    pub muzzle: Option<Point>,
+}
+
+/// One segment of an enemy's path.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub enum Step {
+    Line { to: Point, duration: u32, #[serde(default)] easing: Easing },
+    /// A straight line taking as long as it needs at `speed`.
+    LineAt { to: Point, speed: f32, #[serde(default)] easing: Easing },
+    Bezier { first: Point, second: Point, to: Point, duration: u32, #[serde(default)] easing: Easing },
+    Spline { points: Vec<Point>, duration: u32, #[serde(default)] easing: Easing },
+    Sine { travel: Point, amplitude: f32, period: u32, duration: u32 },
+    Hover(u32),
+}
+
+#[derive(Debug)]
+pub enum StageError {
+    Io(io::Error),
+    Syntax { line: usize, column: usize, message: String },
+    /// A wave spawns an archetype that neither the stage nor the bundled archetypes define.
+    UnknownArchetype { wave: usize, name: String },
+    /// An archetype that isn't a boss has no health, so it would die as soon as it spawned.
+    NoHealth { archetype: String },
+}
+
+/// Sends in a stage's waves as their time comes, and keeps track of whether the
+/// stage has been cleared.
+#[derive(Clone, Debug)]
+pub struct Director {
+    stage: Stage,
+    archetypes: Registry,
+    next_wave: usize,
+    elapsed: Duration,
+}
+
+impl Stage {
+    pub fn parse(source: &str) -> Result<Self, StageError> {
+        let stage: Stage = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_str(source)
+            .map_err(|err| StageError::Syntax {
+                line: err.position.line,
+                column: err.position.col,
+                message: err.code.to_string(),
+            })?;
+
+        stage.check_archetypes()?;
+
+        Ok(stage)
+    }
+
+    /// The bundled archetypes together with the stage's own.
+    pub fn registry(&self) -> Registry {
+        let mut registry = Registry::bundled();
+        registry.extend(self.archetypes.clone());
+        registry
+    }
+
+    /// Make sure every archetype the stage defines can spawn alive, and every
+    /// one it spawns is defined somewhere.
+    pub fn check_archetypes(&self) -> Result<(), StageError> {
+        for (name, archetype) in self.archetypes.iter() {
+            archetype.check(name)?;
+        }
+
+        let registry = self.registry();
+
+        for (index, wave) in self.waves.iter().enumerate() {
+            if let Some(spawn) = wave.enemies.iter().find(|spawn| !registry.contains(&spawn.archetype)) {
+                return Err(StageError::UnknownArchetype { wave: index, name: spawn.archetype.clone() });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, StageError> {
+        Stage::parse(&fs::read_to_string(path).map_err(StageError::Io)?)
+    }
+}
+
+/// A single turret firing straight down, for practice and for tests.
+impl Default for Stage {
+    fn default() -> Self {
+        Stage {
+            name: "Practice".to_string(),
+            archetypes: HashMap::new(),
+            waves: vec![
+                Wave {
+                    at: 0,
+                    enemies: vec![
+                        Spawn {
+                            archetype: "turret".to_string(),
+                            position: (300.0, 20.0),
+                            path: vec![],
+                            emitters: None,
+                            script: None,
+                            muzzle: None,
+                        },
+                    ],
+                },
+            ],
+        }
+    }
+}
+
+impl Spawn {
+    /// The enemy this spawns, or `None` if `archetypes` doesn't know its archetype.
+    pub fn enemy(&self, archetypes: &Registry) -> Option<Enemy> {
+        let mut archetype = archetypes.get(&self.archetype)?.clone();
+
+        if let Some(emitters) = self.emitters.as_ref() {
+            archetype.emitters = emitters.clone();
+        }
+
+        if let Some(script) = self.script.as_ref() {
+            archetype.script = Some(script.clone());
+        }
+
+        if let Some(muzzle) = self.muzzle {
+            archetype.muzzle = Some(muzzle);
+        }
+
+        let mut enemy = archetype.spawn(point(self.position));
+
+        if !self.path.is_empty() {
+            enemy = enemy.with_path(build_path(point(self.position), &self.path));
+        }
+
+        Some(enemy)
+    }
+}
+
+impl Step {
+    /// Add this step to the end of `path`.
+    fn extend(&self, path: Path) -> Path {
+        match self {
+            Step::Line { to, duration, easing } => path.line_to(point(*to), milliseconds(*duration), *easing),
+            Step::LineAt { to, speed, easing } => path.line_at(point(*to), PixelsPerMs(*speed), *easing),
+            Step::Bezier { first, second, to, duration, easing } => {
+                path.bezier_to(point(*first), point(*second), point(*to), milliseconds(*duration), *easing)
+            },
+            Step::Spline { points, duration, easing } => {
+                path.spline_through(points.iter().copied().map(point).collect(), milliseconds(*duration), *easing)
+            },
+            Step::Sine { travel: (x, y), amplitude, period, duration } => {
+                path.sine(
+                    Offset::new(Pixels(*x), Pixels(*y)),
+                    Pixels(*amplitude),
+                    milliseconds(*period),
+                    milliseconds(*duration),
+                )
+            },
+            Step::Hover(duration) => path.hover(milliseconds(*duration)),
+        }
+    }
+}
+
+impl fmt::Display for StageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StageError::Io(err) => write!(f, "could not read stage: {}", err),
+            StageError::Syntax { line, column, message } => {
+                write!(f, "{}:{}: {}", line, column, message)
+            },
+            StageError::UnknownArchetype { wave, name } => {
+                write!(f, "wave {} spawns unknown archetype `{}`", wave, name)
+            },
+            StageError::NoHealth { archetype } => {
+                write!(f, "archetype `{}` has no health and isn't a boss", archetype)
+            },
+        }
+    }
+}
+
+impl error::Error for StageError {}
+
+impl Director {
+    pub fn new(mut stage: Stage) -> Self {
+        stage.waves.sort_by_key(|wave| wave.at);
+
+        Director {
+            archetypes: stage.registry(),
+            stage,
+            next_wave: 0,
+            elapsed: Duration::zero(),
+        }
+    }
+
+    pub fn stage(&self) -> &Stage {
+        &self.stage
+    }
+
+    /// How long the stage has been running.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Whether every wave has been sent in.
+    pub fn finished(&self) -> bool {
+        self.next_wave >= self.stage.waves.len()
+    }
+
+    pub fn archetypes(&self) -> &Registry {
+        &self.archetypes
+    }
+
+    /// The enemies of every wave that is due by now, which have not been sent in yet.
+    /// Enemies of unknown archetypes are skipped, though `Stage::parse` won't allow them.
+    pub fn spawn_due(&mut self) -> Vec<Enemy> {
+        let mut spawned = vec![];
+
+        while let Some(wave) = self.stage.waves.get(self.next_wave) {
+            if milliseconds(wave.at) > self.elapsed {
+                break;
+            }
+
+            spawned.extend(wave.enemies.iter().filter_map(|spawn| spawn.enemy(&self.archetypes)));
+            self.next_wave += 1;
+        }
+
+        spawned
+    }
+
+    /// Move the stage's clock on by `time`.
+    pub fn advance(&mut self, time: Duration) {
+        self.elapsed += time;
+    }
+}
+
+/// A path from `start` through each of `steps` in turn.
+pub fn build_path(start: Position<Pixels>, steps: &[Step]) -> Path {
+    steps.iter().fold(Path::new(start), |path, step| step.extend(path))
+}
+
+fn point((x, y): Point) -> Position<Pixels> {
+    Position::new(Pixels(x), Pixels(y))
+}
+
+fn milliseconds(ms: u32) -> Duration {
+    Duration::milliseconds(ms as i64)
+}
@@ -1,24 +1,22 @@
 use crate::{
     config::ui::UI,
     game::{
-        bullets,
-        enemies,
-        health::{
-            self,
-            Health,
+        clock::{
+            Clock,
+            SystemClock,
         },
         player,
+        render,
+        replay,
+        simulation::Simulation,
+        stage::Stage,
+        timestep::Timestep,
     },
-    physics::{
-        motion,
-        units,
-    },
+    physics::motion::Direction,
 };
 
 
 use chrono::prelude::*;
-use chrono::Duration;
-use chrono::offset::Utc;
 
 use ggez::{
     event::{
@@ -35,210 +33,158 @@ use ggez::{
     GameResult,
 };
 
-/// The main game state container.
+/// The ggez frontend: collects keyboard input, runs as many fixed-length simulation
+/// ticks as the clock says have elapsed and draws the result.
 pub struct State {
-    player: player::Player,
-    enemies: Vec<enemies::Enemy>,
-    bullets: Vec<bullets::Bullet>,
+    simulation: Simulation,
 
+    mode: Mode,
     input_queue: Vec<player::Action>,
-    last_tick_time: DateTime<Utc>,
-    ui: UI,
+    clock: Box<dyn Clock>,
+    timestep: Timestep,
+    last_update_time: DateTime<Utc>,
+}
+
+/// Where the player's actions come from.
+pub enum Mode {
+    /// Read from the keyboard.
+    Live,
+    /// Read from the keyboard and written to a replay file when the game quits.
+    Record(replay::Recorder),
+    /// Read from a replay, ignoring the keyboard.
+    Replay(replay::Playback),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum KeyPress {
+    Pressed,
+    Released,
 }
 
 impl State {
-    pub fn new(ui: UI) -> Self {
-        let tmp_default_enemy = enemies::Enemy::new(
-            motion::Position::new(units::Pixels(300.0), units::Pixels(20.0)),
-            motion::Dimensions::new(units::Pixels(32.0), units::Pixels(44.0)),
-            health::HealthPoints::new(100),
-            vec![
-                bullets::Bullet::new(
-                    bullets::Owner::Enemy,
-                    bullets::Kind::Basic,
-                    motion::Position::new(units::Pixels(316.0), units::Pixels(64.0)),
-                ),
-            ],
-        );
+    pub fn new(ui: UI, seed: u64, stage: Stage, mode: Mode) -> Self {
+        State::with_clock(ui, seed, stage, mode, Box::new(SystemClock))
+    }
+
+    pub fn with_clock(ui: UI, seed: u64, stage: Stage, mode: Mode, clock: Box<dyn Clock>) -> Self {
+        let last_update_time = clock.now();
 
         State {
-            player: player::Player::new(&ui),
-            enemies: vec![tmp_default_enemy],
-            bullets: vec![],
+            simulation: Simulation::with_stage(ui, seed, stage),
+            mode,
             input_queue: vec![],
-            last_tick_time: Utc::now(),
-            ui: ui,
+            clock,
+            timestep: Timestep::default(),
+            last_update_time,
         }
     }
 
-    pub fn add_enemy(&mut self, enemy: enemies::Enemy) {
-        self.enemies.push(enemy);
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
     }
 
-    pub fn process_input_queue(&mut self, time_since_last_tick: Duration) {
-        for input in self.input_queue.iter() {
-            match input {
-                player::Action::Shoot => {
-                    let bullet = bullets::Bullet::new(
-                        bullets::Owner::Player,
-                        bullets::Kind::Basic,
-                        self.player.position,
-                    );
-
-                    self.bullets.push(bullet);
-                },
-
-                mvmt@_ => self.player.reposition(*mvmt, time_since_last_tick),
-            }
-        }
-
-        self.input_queue = vec![];
+    pub fn timestep(&self) -> &Timestep {
+        &self.timestep
     }
 
-    pub fn connect_bullets_with_player(&mut self) -> Vec<usize> {
-        let hitbox = self.player.hitbox_rect();
-        
-        let mut spent_bullet_indices = vec![];
-        let mut bullet_index = 0usize;
-
-        for bullet in self.bullets.iter() {
-            if bullet.owner() == bullets::Owner::Enemy && bullet.hitbox_rect().overlaps(&hitbox) {
-                self.player.take_damage(bullet.damage());
-                spent_bullet_indices.push(bullet_index);
+    /// Run as many ticks as the clock says have passed since the last call, feeding
+    /// the first of them whatever input has been queued up since.
+    pub fn advance(&mut self) {
+        let now = self.clock.now();
+        let ticks = self.timestep.accumulate(now - self.last_update_time);
+        self.last_update_time = now;
+
+        // Inputs wait in the queue until a tick actually runs, and only that first
+        // tick sees them.
+        for _ in 0..ticks {
+            if self.simulation.game_over() {
+                break;
             }
 
-            bullet_index += 1;
-        }
-
-        spent_bullet_indices
-    }
-
-    pub fn connect_bullets_with_enemies(&mut self) -> Vec<usize> {
-        let mut spent_bullet_indices = vec![];
-
-        for enemy in self.enemies.iter_mut() {
-            let hitbox = enemy.hitbox_rect();
-
-            let mut bullet_index = 0usize;
-            for bullet in self.bullets.iter() {
-                if bullet.owner() == bullets::Owner::Player && bullet.hitbox_rect().overlaps(&hitbox) {
-                    enemy.take_damage(bullet.damage());
-                    spent_bullet_indices.push(bullet_index);
-                }
-                bullet_index += 1;
+            match self.next_inputs() {
+                Some(inputs) => self.simulation.tick(&inputs),
+                None => break,
             }
         }
-
-        spent_bullet_indices
     }
 
-    pub fn cleanup_defeated_enemies(&mut self) {
-        let mut remaining_enemies = vec![];
-
-        for enemy in self.enemies.iter() {
-            if !enemy.health().empty() {
-                remaining_enemies.push(enemy.clone());
-            }
+    /// Queue the action `key_code` triggers when pressed, if it triggers one.
+    pub fn press(&mut self, key_code: KeyCode, repeat: bool) {
+        if !self.reads_keyboard() {
+            return;
         }
 
-        self.enemies = remaining_enemies;
+        // Holding the bomb key down sets off one bomb, however long the key repeats for.
+        match action_from_key_code(key_code, KeyPress::Pressed) {
+            Some(player::Action::Bomb) if repeat => {},
+            Some(action) => self.input_queue.push(action),
+            None => {},
+        }
     }
 
-    pub fn trigger_enemy_behaviours(&mut self) {
-        for enemy in self.enemies.iter_mut() {
-            if let Some(bullet) = enemy.fire_bullet() {
-                self.bullets.push(bullet);
-            }
+    /// Queue the action `key_code` triggers when released, if it triggers one.
+    pub fn release(&mut self, key_code: KeyCode) {
+        if !self.reads_keyboard() {
+            return;
         }
-    }
 
-    pub fn update_bullets(&mut self, time_since_last_tick: Duration) {
-        for bullet in self.bullets.iter_mut() {
-            bullet.reposition(time_since_last_tick);
+        if let Some(action) = action_from_key_code(key_code, KeyPress::Released) {
+            self.input_queue.push(action);
         }
     }
 
-    pub fn position_player_in_game_space(&mut self) {
-        let max_x = self.ui.width - self.player.dimensions.width.value();
-        let max_y = self.ui.height - self.player.dimensions.height.value();
-
-        let new_x = if self.player.position.x.value() < 0.0 {
-            0.0
-        } else if self.player.position.x.value() > max_x {
-            max_x
-        } else {
-            self.player.position.x.value()
-        };
-
-        let new_y = if self.player.position.y.value() < 0.0 {
-            0.0
-        } else if self.player.position.y.value() > max_y {
-            max_y
-        } else {
-            self.player.position.y.value()
-        };
-
-        self.player.position = motion::Position::new(units::Pixels(new_x), units::Pixels(new_y));
+    /// Whether keypresses become actions: not while watching a replay, nor once
+    /// the game is over and there's nothing left to play.
+    fn reads_keyboard(&self) -> bool {
+        !matches!(self.mode, Mode::Replay(_)) && !self.simulation.game_over()
     }
 
-    pub fn cleanup_spent_bullets(&mut self, indices: Vec<usize>) {
-        let mut remaining_bullets: Vec<bullets::Bullet> = vec![];
-        let mut index = 0usize;
-
-        for bullet in self.bullets.iter() {
-            let mut should_include = true;
+    /// The actions to feed the next tick, or `None` if there is nothing left to play.
+    fn next_inputs(&mut self) -> Option<Vec<player::Action>> {
+        let tick = self.simulation.current_tick();
 
-            for excluded in indices.iter() {
-                if index == *excluded {
-                    should_include = false;
-                    break;
-                }
-            }
-
-            if should_include {
-                remaining_bullets.push(bullet.clone());
-            }
+        let inputs = match &self.mode {
+            Mode::Replay(playback) => playback.actions_at(tick)?.to_vec(),
+            _ => std::mem::take(&mut self.input_queue),
+        };
 
-            index += 1;
+        if let Mode::Record(recorder) = &mut self.mode {
+            recorder.record(tick, &inputs);
         }
 
-        self.bullets = remaining_bullets;
+        Some(inputs)
     }
+}
 
-    pub fn cleanup_out_of_bounds_bullets(&mut self) {
-        let ui_rect = self.ui.hitbox_rect();
-
-        let mut remaining_bullets: Vec<bullets::Bullet> = vec![];
-
-        for bullet in self.bullets.iter() {
-            if bullet.hitbox_rect().overlaps(&ui_rect) {
-                remaining_bullets.push(bullet.clone());
-            }
-        }
-
-        self.bullets = remaining_bullets;
+/// Map a keyboard key to the player action it triggers, if any.
+pub fn action_from_key_code(key_code: KeyCode, key: KeyPress) -> Option<player::Action> {
+    let action = if key == KeyPress::Released {
+        player::Action::StopMoving
+    } else {
+        player::Action::Move
+    };
+
+    match key_code {
+        KeyCode::Up    => Some(action(Direction::Up)),
+        KeyCode::Down  => Some(action(Direction::Down)),
+        KeyCode::Left  => Some(action(Direction::Left)),
+        KeyCode::Right => Some(action(Direction::Right)),
+        KeyCode::Space => Some(match key {
+            KeyPress::Pressed  => player::Action::StartFiring,
+            KeyPress::Released => player::Action::StopFiring,
+        }),
+        KeyCode::LShift | KeyCode::RShift => Some(match key {
+            KeyPress::Pressed  => player::Action::Focus,
+            KeyPress::Released => player::Action::Unfocus,
+        }),
+        KeyCode::X if key == KeyPress::Pressed => Some(player::Action::Bomb),
+        _              => None,
     }
 }
 
 impl EventHandler<GameError> for State {
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        let time_since_last_tick = Utc::now() - self.last_tick_time;
-
-        self.process_input_queue(time_since_last_tick);
-        self.position_player_in_game_space();
-        let spent_bullet_indices = self.connect_bullets_with_player();
-        self.cleanup_spent_bullets(spent_bullet_indices);
-        println!("Player health: {:?}", self.player.health());
-
-        let spent_bullet_indices = self.connect_bullets_with_enemies();
-        self.cleanup_defeated_enemies();
-        self.trigger_enemy_behaviours();
-        
-        self.cleanup_spent_bullets(spent_bullet_indices);
-        self.update_bullets(time_since_last_tick);
-        self.cleanup_out_of_bounds_bullets();
-
-        self.last_tick_time = Utc::now();
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        self.advance();
 
         Ok(())
     }
@@ -246,14 +192,24 @@ impl EventHandler<GameError> for State {
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
         graphics::clear(ctx, Color::WHITE);
 
-        self.player.draw(ctx)?;
+        let alpha = self.timestep.alpha();
+
+        let world = self.simulation.world();
 
-        for enemy in self.enemies.iter() {
-            enemy.draw(ctx)?;
+        render::draw_entities(ctx, world, alpha)?;
+        render::draw_explosions(ctx, world, alpha)?;
+        render::draw_focused_hitboxes(ctx, world, alpha)?;
+
+        render::draw_hud(ctx, world, self.simulation.player(), self.simulation.ui())?;
+
+        if let Some(boss) = world.boss() {
+            render::draw_boss_health(ctx, world, boss, self.simulation.ui())?;
         }
 
-        for bullet in self.bullets.iter() {
-            bullet.draw(ctx)?;
+        if self.simulation.game_over() {
+            render::draw_game_over(ctx, self.simulation.ui())?;
+        } else if self.simulation.stage_cleared() {
+            render::draw_stage_clear(ctx, self.simulation.ui(), &self.simulation.director().stage().name)?;
         }
 
         graphics::present(ctx)?;
@@ -267,11 +223,9 @@ impl EventHandler<GameError> for State {
         _ctx: &mut Context,
         key_code: KeyCode,
         _key_mods: KeyMods,
-        _repeat: bool
+        repeat: bool
     ) {
-        if let Some(action) = player::Action::from_key_code(key_code, player::KeyPress::Pressed) {
-            self.input_queue.push(action);
-        }
+        self.press(key_code, repeat);
     }
 
     fn key_up_event(
@@ -280,8 +234,16 @@ impl EventHandler<GameError> for State {
         key_code: KeyCode,
         _key_mods: KeyMods,
     ) {
-        if let Some(action) = player::Action::from_key_code(key_code, player::KeyPress::Released) {
-            self.input_queue.push(action);
+        self.release(key_code);
+    }
+
+    fn quit_event(&mut self, _ctx: &mut Context) -> bool {
+        if let Mode::Record(recorder) = &self.mode {
+            if let Err(err) = recorder.save() {
+                eprintln!("Failed to save replay: {}", err);
+            }
         }
+
+        false
     }
 }
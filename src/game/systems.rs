@@ -0,0 +1,761 @@
+//! The systems that run the game. Each one works through the entities in a `World`
+//! that have the components it needs; `Simulation::tick` runs them in order.
+
+use crate::{
+    config::ui::UI,
+    game::{
+        bombs,
+        bullets::HitPolicy,
+        components::{Faction, Velocity},
+        drones::{self, Drone},
+        enemies,
+        health::{Health, HealthPoints},
+        items::{self, Collectible, Item},
+        lives::{self, Lives},
+        patterns::Target,
+        player::Action,
+        rng::Rng,
+        world::{Entity, World},
+    },
+    physics::{
+        collision,
+        motion::{Kinematics, Offset, Position},
+        spatial::Grid,
+        units::Pixels,
+    },
+};
+
+use chrono::Duration;
+
+
+/// Which sort of entity a system should work on, told apart by the components
+/// only that sort has.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Role {
+    Player,
+    Enemy,
+    Bullet,
+    Item,
+}
+
+/// A bullet striking something on the other side.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Hit {
+    pub bullet: Entity,
+    pub target: Entity,
+}
+
+/// What the collision systems found this tick.
+#[derive(Clone, Debug, Default)]
+pub struct Impacts {
+    /// Every hit, in the order their damage is to be dealt.
+    pub hits: Vec<Hit>,
+    /// The bullets used up by their hits, which should be despawned.
+    pub spent: Vec<Entity>,
+}
+
+/// Where the player's bullets are, for `enemy_collisions` to look hits up in,
+/// along with scratch space kept between ticks to save allocating it again.
+#[derive(Clone, Debug)]
+pub struct BulletIndex {
+    grid: Grid,
+    candidates: Vec<usize>,
+    contacts: Vec<(Entity, Entity)>,
+}
+
+impl BulletIndex {
+    /// An empty index over `bounds`, usually the screen, with cells `cell_size` pixels across.
+    pub fn new(bounds: collision::Rect, cell_size: f32) -> Self {
+        BulletIndex {
+            grid: Grid::new(bounds, cell_size),
+            candidates: vec![],
+            contacts: vec![],
+        }
+    }
+}
+
+impl Impacts {
+    pub fn clear(&mut self) {
+        self.hits.clear();
+        self.spent.clear();
+    }
+}
+
+impl Role {
+    /// Whether `entity` plays this role.
+    fn of(&self, entity: Entity, world: &World) -> bool {
+        match self {
+            Role::Player => world.pilots.contains(entity),
+            Role::Enemy  => world.armaments.contains(entity),
+            Role::Bullet => world.projectiles.contains(entity),
+            Role::Item   => world.pickups.contains(entity),
+        }
+    }
+}
+
+/// Apply the player's `inputs` to `player`, setting off a bomb for every bomb.
+pub fn apply_inputs(world: &mut World, player: Entity, inputs: &[Action]) {
+    for input in inputs.iter() {
+        if *input == Action::Bomb {
+            bomb(world, player);
+        } else if let Some(pilot) = world.pilots.get_mut(player) {
+            pilot.apply_action(*input);
+        }
+    }
+}
+
+/// Set off one of `player`'s bombs, if they're alive, have one and aren't already
+/// bombing: every enemy bullet is cleared, every enemy takes `bombs::DAMAGE` and
+/// the player is shielded for a while. Bombing just after a fatal hit saves the
+/// player, with their health restored. Returns whether a bomb went off.
+pub fn bomb(world: &mut World, player: Entity) -> bool {
+    let alive = world.lives.get(player).is_none_or(Lives::is_alive);
+
+    if !alive || !world.bombs.get_mut(player).is_some_and(|bombs| bombs.take()) {
+        return false;
+    }
+
+    if let Some(lives) = world.lives.get_mut(player) {
+        if lives.is_hit() {
+            if let Some(health) = world.healths.get_mut(player) {
+                *health = HealthPoints::new(health.maximum());
+            }
+        }
+
+        lives.shield(bombs::INVULNERABILITY);
+    }
+
+    clear_bullets(world, Faction::Enemy);
+
+    for (enemy, _) in world.armaments.iter() {
+        let boss = world.bosses.get(enemy).is_some();
+
+        if let Some(health) = world.healths.get_mut(enemy) {
+            // Bosses are left with at least a sliver of the phase's health, so it
+            // takes the player's shots to move them on.
+            let damage = if boss { bombs::BOSS_DAMAGE.min(health.current().saturating_sub(1)) } else { bombs::DAMAGE };
+
+            health.take_damage(HealthPoints::new(damage));
+        }
+    }
+
+    true
+}
+
+/// Count down every bomb that's going off, so that another can be set off once it's over.
+pub fn bomb_timers(world: &mut World, time: Duration) {
+    for (_, bombs) in world.bombs.iter_mut() {
+        bombs.update(time);
+    }
+}
+
+/// Let the player's weapons cool down, firing whatever they can while the trigger
+/// is held. Dead players don't fire.
+pub fn player_fire(world: &mut World, time: Duration, rng: &mut Rng) {
+    let mut fired = vec![];
+
+    for (player, pilot) in world.pilots.iter_mut() {
+        if !world.lives.get(player).is_none_or(Lives::is_alive) {
+            continue;
+        }
+
+        if let Some(transform) = world.transforms.get(player) {
+            fired.extend(pilot.update_weapons(time, transform.position, None, rng));
+        }
+    }
+
+    for bullet in fired {
+        world.spawn(bullet);
+    }
+}
+
+/// Set the velocity of anything piloted by the player to match the directions they're
+/// moving in, or hold it still while the player is dead.
+pub fn steer(world: &mut World) {
+    for (entity, pilot) in world.pilots.iter() {
+        let velocity = if world.lives.get(entity).is_none_or(Lives::is_alive) {
+            pilot.velocity()
+        } else {
+            Default::default()
+        };
+
+        if let Some(component) = world.velocities.get_mut(entity) {
+            *component = Velocity(Kinematics::new(velocity));
+        }
+    }
+}
+
+/// Move everything of the given `role` by `time`: along its path if it has one,
+/// otherwise under its own velocity.
+pub fn movement(world: &mut World, time: Duration, role: Role) {
+    // Taken out of the world while moving, so the rest of it can still be looked at.
+    let mut transforms = std::mem::take(&mut world.transforms);
+
+    for (entity, transform) in transforms.iter_mut() {
+        if !role.of(entity, world) {
+            continue;
+        }
+
+        let position = match (world.routes.get_mut(entity), world.velocities.get_mut(entity)) {
+            (Some(route), _) => route.advance(time),
+            (None, Some(velocity)) => transform.position + velocity.0.integrate(time),
+            (None, None) => continue,
+        };
+
+        transform.move_to(position);
+    }
+
+    world.transforms = transforms;
+}
+
+/// Keep `entity` entirely within the screen.
+pub fn confine(world: &mut World, entity: Entity, ui: &UI) {
+    if let Some(transform) = world.transforms.get_mut(entity) {
+        let max_x = Pixels(ui.width) - transform.dimensions.width;
+        let max_y = Pixels(ui.height) - transform.dimensions.height;
+
+        transform.position = Position::new(
+            transform.position.x.clamp(Pixels(0.0), max_x),
+            transform.position.y.clamp(Pixels(0.0), max_y),
+        );
+    }
+}
+
+/// Remember where everything with a trail is now, for its drones to follow.
+pub fn record_trails(world: &mut World) {
+    for (entity, trail) in world.trails.iter_mut() {
+        if let Some(transform) = world.transforms.get(entity) {
+            trail.push(transform.centre());
+        }
+    }
+}
+
+/// Give `player` a drone for each weapon level they have, adding new ones at the
+/// back of the formation, tight if they're focused, and taking the rearmost away first. Drones whose player
+/// is gone go with them.
+pub fn sync_drones(world: &mut World, player: Entity) {
+    let orphaned: Vec<Entity> = world.satellites.iter()
+        .filter(|(_, satellite)| !world.contains(satellite.owner()))
+        .map(|(drone, _)| drone)
+        .collect();
+
+    for drone in orphaned {
+        world.despawn(drone);
+    }
+
+    let wanted = world.pilots.get(player).map_or(0, |pilot| pilot.level() as usize);
+    let focused = world.pilots.get(player).is_some_and(|pilot| pilot.is_focused());
+    let mut drones: Vec<(usize, Entity)> = world.drones(player)
+        .filter_map(|drone| world.satellites.get(drone).map(|satellite| (satellite.slot(), drone)))
+        .collect();
+    drones.sort_by_key(|&(slot, _)| slot);
+
+    while drones.len() > wanted {
+        if let Some((_, drone)) = drones.pop() {
+            world.despawn(drone);
+        }
+    }
+
+    let centre = match world.trails.get(player).and_then(|trail| trail.at(0)) {
+        Some(centre) => centre,
+        None => return,
+    };
+
+    for slot in drones.len()..wanted {
+        world.spawn(Drone::new(player, slot, centre + drones::formation(slot, wanted, focused)));
+    }
+}
+
+/// Fly every drone to its place in its player's formation, a little way behind
+/// where the player has been.
+pub fn fly_drones(world: &mut World) {
+    let formations: Vec<(Entity, usize)> = world.trails.entities()
+        .map(|owner| (owner, world.drones(owner).count()))
+        .collect();
+
+    for (owner, count) in formations {
+        let focused = world.pilots.get(owner).is_some_and(|pilot| pilot.is_focused());
+
+        for (drone, satellite) in world.satellites.iter() {
+            if satellite.owner() != owner {
+                continue;
+            }
+
+            let centre = match world.trails.get(owner).and_then(|trail| trail.at(satellite.delay())) {
+                Some(centre) => centre + drones::formation(satellite.slot(), count, focused),
+                None => continue,
+            };
+
+            if let Some(transform) = world.transforms.get_mut(drone) {
+                transform.move_to(drones::top_left(centre, transform.dimensions));
+            }
+        }
+    }
+}
+
+/// Let drones' weapons cool down, firing them alongside their players while the
+/// trigger is held. Drones fire at their player's weapon level, but not while
+/// the player is dead.
+pub fn drone_fire(world: &mut World, time: Duration, rng: &mut Rng) {
+    let mut fired = vec![];
+
+    for (drone, satellite) in world.satellites.iter_mut() {
+        let owner = satellite.owner();
+        let alive = world.lives.get(owner).is_none_or(Lives::is_alive);
+        let (held, level) = match world.pilots.get(owner) {
+            Some(pilot) => (alive && pilot.is_firing(), pilot.level()),
+            None => continue,
+        };
+
+        if let Some(transform) = world.transforms.get(drone) {
+            let volley = satellite.weapon_mut().update(time, held, transform.position, None, rng);
+
+            fired.extend(volley.into_iter().map(|bullet| bullet.with_level(level)));
+        }
+    }
+
+    for bullet in fired {
+        world.spawn(bullet);
+    }
+}
+
+/// Rebuild the index of where the player's bullets are, which `enemy_collisions`
+/// looks hits up in.
+///
+/// Enemy bullets aren't indexed: there is only the one player for them to hit,
+/// and checking each against it is quicker than sorting them into cells.
+pub fn index_bullets(world: &World, index: &mut BulletIndex) {
+    index.grid.clear();
+
+    for bullet in world.bullets() {
+        if world.factions.get(bullet) == Some(&Faction::Player) {
+            if let Some(hitbox) = world.hitbox(bullet) {
+                index.grid.insert(bullet.index(), &hitbox.bounds());
+            }
+        }
+    }
+}
+
+/// Find every enemy bullet touching the player. Each is used up by the hit. Bullets
+/// pass straight through a player who is dead or invulnerable.
+pub fn player_collisions(world: &World, impacts: &mut Impacts) {
+    for player in world.pilots.entities() {
+        if !world.lives.get(player).is_none_or(Lives::is_vulnerable) {
+            continue;
+        }
+
+        let hitbox = match world.hitbox(player) {
+            Some(hitbox) => hitbox,
+            None => continue,
+        };
+        let bounds = hitbox.bounds();
+
+        for bullet in world.bullets() {
+            if world.factions.get(bullet) != Some(&Faction::Enemy) {
+                continue;
+            }
+
+            let touching = world.hitbox(bullet)
+                .is_some_and(|shape| shape.bounds().overlaps(&bounds) && shape.overlaps(&hitbox));
+
+            if touching {
+                impacts.hits.push(Hit { bullet, target: player });
+                impacts.spent.push(bullet);
+            }
+        }
+    }
+}
+
+/// Find the enemies the player's bullets hit, as each bullet's `HitPolicy` says.
+/// Relies on `index_bullets` having been called since the bullets last moved.
+pub fn enemy_collisions(world: &mut World, index: &mut BulletIndex, impacts: &mut Impacts) {
+    let BulletIndex { grid, candidates, contacts } = index;
+    contacts.clear();
+
+    for enemy in world.enemies() {
+        let hitbox = match world.hitbox(enemy) {
+            Some(hitbox) => hitbox,
+            None => continue,
+        };
+
+        grid.query(&hitbox.bounds(), candidates);
+
+        for &index in candidates.iter() {
+            let bullet = world.entity_at(index).expect("the index is up to date");
+
+            if world.hitbox(bullet).is_some_and(|shape| shape.overlaps(&hitbox)) {
+                contacts.push((bullet, enemy));
+            }
+        }
+    }
+
+    // Group the contacts by bullet, keeping each bullet's enemies in the order they're stored.
+    contacts.sort_by_key(|(bullet, _)| bullet.index());
+
+    for contacts in contacts.chunk_by(|a, b| a.0 == b.0) {
+        let bullet = contacts[0].0;
+        let policy = world.projectiles.get(bullet).expect("the index is up to date").hit_policy();
+
+        let spent = match policy {
+            HitPolicy::First => {
+                impacts.hits.push(Hit { bullet, target: contacts[0].1 });
+                true
+            },
+            HitPolicy::Pierce(_) => {
+                let projectile = world.projectiles.get_mut(bullet).expect("the index is up to date");
+                let mut spent = false;
+
+                for &(_, enemy) in contacts.iter() {
+                    if projectile.has_pierced(enemy) {
+                        continue;
+                    }
+
+                    impacts.hits.push(Hit { bullet, target: enemy });
+                    spent = projectile.pierce(enemy);
+
+                    if spent {
+                        break;
+                    }
+                }
+
+                spent
+            },
+            HitPolicy::Area { radius } => {
+                let centre = world.hitbox(bullet).expect("bullets have hitboxes").bounds().centre();
+                let blast = collision::Shape::Circle { centre, radius };
+
+                for enemy in world.enemies() {
+                    if world.hitbox(enemy).is_some_and(|shape| shape.overlaps(&blast)) {
+                        impacts.hits.push(Hit { bullet, target: enemy });
+                    }
+                }
+                true
+            },
+        };
+
+        if spent {
+            impacts.spent.push(bullet);
+        }
+    }
+}
+
+/// Take the damage of each bullet in `hits` off the health of whatever it hit.
+pub fn damage(world: &mut World, hits: &[Hit]) {
+    for hit in hits {
+        let damage = match world.projectiles.get(hit.bullet) {
+            Some(projectile) => projectile.damage(),
+            None => continue,
+        };
+
+        if let Some(health) = world.healths.get_mut(hit.target) {
+            *health = *health - damage;
+        }
+    }
+}
+
+/// Fatally hit players whose health has run out, kill them off if they don't bomb
+/// in time and count down to their respawns, bringing them back at their spawn
+/// point with full health.
+pub fn player_lives(world: &mut World, time: Duration) {
+    for (player, lives) in world.lives.iter_mut() {
+        if lives.is_vulnerable() && world.healths.get(player).is_some_and(HealthPoints::empty) {
+            lives.hit();
+            continue;
+        }
+
+        if lives.update(time) == Some(lives::Event::Respawned) {
+            if let Some(transform) = world.transforms.get_mut(player) {
+                transform.teleport(lives.spawn_point());
+            }
+
+            if let Some(health) = world.healths.get_mut(player) {
+                *health = HealthPoints::new(health.maximum());
+            }
+        }
+    }
+}
+
+/// Move bosses on through their phases, returning whether any phase ended.
+pub fn boss_phases(world: &mut World, time: Duration) -> bool {
+    let mut phase_ended = false;
+
+    for (boss, phases) in world.bosses.iter_mut() {
+        let position = match world.transforms.get(boss) {
+            Some(transform) => transform.position,
+            None => continue,
+        };
+
+        let (health, armament, velocity) = match (
+            world.healths.get_mut(boss),
+            world.armaments.get_mut(boss),
+            world.velocities.get_mut(boss),
+        ) {
+            (Some(health), Some(armament), Some(velocity)) => (health, armament, velocity),
+            _ => continue,
+        };
+
+        let mut route = world.routes.remove(boss);
+        phase_ended |= enemies::update_phase(phases, time, position, health, armament, velocity, &mut route);
+
+        if let Some(route) = route {
+            world.routes.insert(boss, route);
+        }
+    }
+
+    phase_ended
+}
+
+/// Advance every enemy's weapons, spawning whatever they fire. Aimed patterns are
+/// aimed at `target`.
+pub fn enemy_fire(world: &mut World, time: Duration, target: Option<Target>, rng: &mut Rng) {
+    let mut fired = vec![];
+
+    for (enemy, armament) in world.armaments.iter_mut() {
+        let position = match world.transforms.get(enemy) {
+            Some(transform) => transform.position,
+            None => continue,
+        };
+
+        if let Some(velocity) = world.velocities.get_mut(enemy) {
+            fired.extend(armament.fire(time, position, &mut velocity.0, target, rng));
+        }
+    }
+
+    for bullet in fired {
+        world.spawn(bullet);
+    }
+}
+
+/// Turn every homing bullet towards its target: the enemy it's locked on to, or
+/// else the nearest enemy, which it locks on to, for the player's bullets, and
+/// `player` for enemies'.
+pub fn home_in(world: &mut World, time: Duration, player: Option<Position<Pixels>>) {
+    let enemies: Vec<(Entity, Position<Pixels>)> = world.enemies()
+        .filter_map(|enemy| world.transforms.get(enemy).map(|transform| (enemy, transform.centre())))
+        .collect();
+
+    for (bullet, projectile) in world.projectiles.iter_mut() {
+        let homing = match projectile.homing_mut() {
+            Some(homing) => homing,
+            None => continue,
+        };
+
+        let (centre, faction) = match (world.transforms.get(bullet), world.factions.get(bullet)) {
+            (Some(transform), Some(faction)) => (transform.centre(), *faction),
+            _ => continue,
+        };
+
+        let target = match faction {
+            Faction::Enemy => player,
+            Faction::Player => {
+                let locked = homing.lock()
+                    .and_then(|lock| enemies.iter().find(|&&(enemy, _)| enemy == lock));
+                let nearest = || enemies.iter()
+                    .min_by(|(_, a), (_, b)| (*a - centre).length().value().total_cmp(&(*b - centre).length().value()));
+
+                locked.or_else(nearest).map(|&(enemy, position)| {
+                    homing.lock_on(enemy);
+                    position
+                })
+            },
+        };
+
+        if let Some(velocity) = world.velocities.get_mut(bullet) {
+            homing.steer(time, centre, &mut velocity.0, target);
+        }
+    }
+}
+
+/// Run a tick of every bullet's script, spawning whatever they fire. Aimed shots
+/// are aimed at `target`.
+pub fn bullet_scripts(world: &mut World, target: Option<Position<Pixels>>) {
+    let mut fired = vec![];
+
+    for (bullet, projectile) in world.projectiles.iter_mut() {
+        let (position, faction) = match (world.transforms.get(bullet), world.factions.get(bullet)) {
+            (Some(transform), Some(faction)) => (transform.centre(), *faction),
+            _ => continue,
+        };
+
+        if let Some(velocity) = world.velocities.get_mut(bullet) {
+            fired.extend(projectile.run_script(position, &mut velocity.0, faction, target));
+        }
+    }
+
+    for bullet in fired {
+        world.spawn(bullet);
+    }
+}
+
+/// Despawn every enemy that has been shot down, leaving behind whichever of their
+/// drops come up, and return the score they were worth.
+pub fn cleanup_defeated(world: &mut World, rng: &mut Rng) -> u64 {
+    let defeated: Vec<Entity> = world.enemies()
+        .filter(|&enemy| world.health(enemy).is_some_and(|health| health.empty()))
+        .collect();
+
+    let mut score = 0;
+    let mut dropped = vec![];
+
+    for enemy in defeated {
+        if let (Some(reward), Some(transform)) = (world.rewards.get(enemy), world.transforms.get(enemy)) {
+            score += reward.score;
+
+            for drop in reward.drops.iter() {
+                if rng.next_f32() < drop.chance {
+                    // Scattered a little, so several drops don't land in a pile.
+                    let scatter = Offset::new(Pixels(rng.range(-16.0, 16.0)), Pixels(rng.range(-16.0, 16.0)));
+
+                    dropped.push(Collectible::new(drop.item, transform.centre() + scatter));
+                }
+            }
+        }
+
+        world.despawn(enemy);
+    }
+
+    for item in dropped {
+        world.spawn(item);
+    }
+
+    score
+}
+
+/// Despawn enemies that have flown off `screen` at the end of their paths, and so
+/// are never coming back.
+pub fn cleanup_departed(world: &mut World, screen: &collision::Rect) {
+    let departed: Vec<Entity> = world.enemies()
+        .filter(|&enemy| {
+            let finished = world.routes.get(enemy).is_some_and(|route| route.finished());
+            let on_screen = world.transforms.get(enemy).is_some_and(|transform| transform.rect().overlaps(screen));
+
+            finished && !on_screen
+        })
+        .collect();
+
+    for enemy in departed {
+        world.despawn(enemy);
+    }
+}
+
+/// Despawn the bullets that were used up by what they hit.
+pub fn cleanup_spent(world: &mut World, spent: &[Entity]) {
+    for &bullet in spent {
+        world.despawn(bullet);
+    }
+}
+
+/// Despawn bullets that have left `screen` or been removed by their scripts.
+pub fn cleanup_out_of_bounds(world: &mut World, screen: &collision::Rect) {
+    let gone: Vec<Entity> = world.projectiles.iter()
+        .filter(|&(bullet, projectile)| {
+            let on_screen = world.transforms.get(bullet).is_some_and(|transform| transform.rect().overlaps(screen));
+
+            projectile.vanished() || !on_screen
+        })
+        .map(|(bullet, _)| bullet)
+        .collect();
+
+    for bullet in gone {
+        world.despawn(bullet);
+    }
+}
+
+/// Draw items in to `player`: every item while they're above the point of
+/// collection, and those within `items::MAGNET_RADIUS` while they're focused. Items
+/// that have been drawn in head for the player while they're alive, and otherwise
+/// drift down the screen.
+pub fn attract_items(world: &mut World, player: Entity, ui: &UI) {
+    let alive = world.lives.get(player).is_none_or(Lives::is_alive);
+    let centre = world.transforms.get(player).filter(|_| alive).map(|transform| transform.centre());
+
+    let above_line = world.transforms.get(player).is_some_and(|transform| transform.position.y.value() < ui.height * items::COLLECTION_LINE);
+    let focused = world.pilots.get(player).is_some_and(|pilot| pilot.is_focused());
+
+    for (item, pickup) in world.pickups.iter_mut() {
+        let transform = match world.transforms.get(item) {
+            Some(transform) => transform,
+            None => continue,
+        };
+
+        if let Some(centre) = centre {
+            let in_reach = (transform.centre() - centre).length().value() < items::MAGNET_RADIUS;
+
+            if above_line || (focused && in_reach) {
+                pickup.magnetise();
+            }
+        }
+
+        if let Some(velocity) = world.velocities.get_mut(item) {
+            *velocity = Velocity(Kinematics::new(pickup.velocity(transform.centre(), centre)));
+        }
+    }
+}
+
+/// Give `player` every item they're touching, returning the score the items were
+/// worth. Items can be picked up anywhere on the player, not just their hitbox.
+pub fn collect_items(world: &mut World, player: Entity) -> u64 {
+    if !world.lives.get(player).is_none_or(Lives::is_alive) {
+        return 0;
+    }
+
+    let reach = match world.transforms.get(player) {
+        Some(transform) => transform.rect(),
+        None => return 0,
+    };
+
+    let collected: Vec<(Entity, Item)> = world.pickups.iter()
+        .filter(|&(item, _)| world.hitbox(item).is_some_and(|shape| shape.bounds().overlaps(&reach)))
+        .map(|(item, pickup)| (item, pickup.item()))
+        .collect();
+
+    let mut score = 0;
+
+    for (entity, item) in collected {
+        match item {
+            Item::Power => {
+                if let Some(pilot) = world.pilots.get_mut(player) {
+                    pilot.power_up(1);
+                }
+            },
+            Item::Points => score += items::POINTS,
+            Item::Life => {
+                if let Some(lives) = world.lives.get_mut(player) {
+                    lives.gain();
+                }
+            },
+            Item::Bomb => {
+                if let Some(bombs) = world.bombs.get_mut(player) {
+                    bombs.replenish(1);
+                }
+            },
+        }
+
+        world.despawn(entity);
+    }
+
+    score
+}
+
+/// Despawn items that have drifted off the bottom of `screen` without being collected.
+pub fn cleanup_uncollected(world: &mut World, screen: &collision::Rect) {
+    let missed: Vec<Entity> = world.items()
+        .filter(|&item| !world.transforms.get(item).is_some_and(|transform| transform.rect().overlaps(screen)))
+        .collect();
+
+    for item in missed {
+        world.despawn(item);
+    }
+}
+
+/// Despawn every bullet fired by `faction`, as happens to enemy bullets between
+/// phases of a boss fight.
+pub fn clear_bullets(world: &mut World, faction: Faction) {
+    let cleared: Vec<Entity> = world.bullets()
+        .filter(|&bullet| world.factions.get(bullet) == Some(&faction))
+        .collect();
+
+    for bullet in cleared {
+        world.despawn(bullet);
+    }
+}
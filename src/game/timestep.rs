@@ -0,0 +1,62 @@
+use chrono::Duration;
+
+
+/// The number of simulation ticks per second of game time.
+pub const TICKS_PER_SECOND: i64 = 60;
+
+/// The most ticks a single update may run before the remaining backlog is dropped,
+/// so that a long stall does not make the game spend forever catching up.
+const MAX_TICKS_PER_UPDATE: u32 = 8;
+
+/// Converts the variable amount of time between frames into a whole number of
+/// fixed-length simulation ticks.
+pub struct Timestep {
+    tick_length: Duration,
+    accumulator: Duration,
+}
+
+impl Timestep {
+    pub fn new(ticks_per_second: i64) -> Self {
+        Timestep {
+            tick_length: Duration::nanoseconds(1_000_000_000 / ticks_per_second),
+            accumulator: Duration::zero(),
+        }
+    }
+
+    pub fn tick_length(&self) -> Duration {
+        self.tick_length
+    }
+
+    /// Add `elapsed` time to the accumulator and return how many ticks should be run.
+    pub fn accumulate(&mut self, elapsed: Duration) -> u32 {
+        self.accumulator += elapsed;
+
+        let mut ticks = 0u32;
+        while self.accumulator >= self.tick_length {
+            self.accumulator -= self.tick_length;
+            ticks += 1;
+
+            if ticks == MAX_TICKS_PER_UPDATE {
+                self.accumulator = Duration::zero();
+                break;
+            }
+        }
+
+        ticks
+    }
+
+    /// How far between the last tick and the next one the accumulator currently is,
+    /// from 0.0 to 1.0, for interpolating what gets drawn.
+    pub fn alpha(&self) -> f32 {
+        let leftover = self.accumulator.num_nanoseconds().unwrap_or(0) as f32;
+        let length = self.tick_length.num_nanoseconds().unwrap_or(1) as f32;
+
+        leftover / length
+    }
+}
+
+impl Default for Timestep {
+    fn default() -> Self {
+        Timestep::new(TICKS_PER_SECOND)
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{
+    config::ui::UI,
+    game::{
+        components::Faction,
+        health::HealthPoints,
+        replay::{Replay, ReplayError},
+        simulation::Simulation,
+        world::{self, World},
+    },
+    physics::{
+        motion::Position,
+        units,
+    },
+};
+
+use std::{
+    fmt,
+    fs,
+    io,
+    path::Path,
+};
+
+
+/// A fingerprint of every entity in the simulation after one tick.
+///
+/// Comparing traces tick by tick tells whether two runs of the same replay played
+/// out identically, and if not, exactly where they first disagreed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TickTrace {
+    pub tick: u64,
+    pub player: u64,
+    pub enemies: Vec<u64>,
+    pub bullets: Vec<u64>,
+}
+
+/// The fingerprints of a whole game, one per tick.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub ticks: Vec<TickTrace>,
+}
+
+/// Which entity first differed between two traces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Entity {
+    Player,
+    Enemy(usize),
+    Bullet(usize),
+    EnemyCount { expected: usize, found: usize },
+    BulletCount { expected: usize, found: usize },
+    TraceLength { expected: usize, found: usize },
+}
+
+/// The first point at which two traces disagree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub tick: u64,
+    pub entity: Entity,
+}
+
+/// A 64-bit FNV-1a hash, used because its output is stable across platforms and
+/// compiler versions, unlike the standard library's hasher.
+pub(crate) struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub(crate) fn new() -> Self {
+        Fingerprint(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        for byte in bytes.iter() {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+
+        self
+    }
+
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn u64(&mut self, value: u64) -> &mut Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn f32(&mut self, value: f32) -> &mut Self {
+        self.u32(value.to_bits())
+    }
+
+    fn position(&mut self, position: Position<units::Pixels>) -> &mut Self {
+        self.f32(position.x.value()).f32(position.y.value())
+    }
+
+    fn health(&mut self, health: HealthPoints) -> &mut Self {
+        self.u32(health.current()).u32(health.maximum())
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// The fingerprint of whatever `entity` is: where it is, how healthy it is and,
+/// for bullets, whose side it is on and what kind it is.
+pub fn fingerprint(world: &World, entity: world::Entity) -> u64 {
+    let mut fingerprint = Fingerprint::new();
+
+    if let Some(transform) = world.transforms.get(entity) {
+        fingerprint.position(transform.position);
+    }
+
+    if let Some(health) = world.health(entity) {
+        fingerprint.health(health);
+    }
+
+    if let Some(projectile) = world.projectiles.get(entity) {
+        let faction = world.factions.get(entity).copied().unwrap_or(Faction::Enemy);
+
+        fingerprint.u32(faction as u32).u32(projectile.kind() as u32);
+    }
+
+    fingerprint.finish()
+}
+
+impl TickTrace {
+    pub fn capture(simulation: &Simulation) -> Self {
+        let world = simulation.world();
+
+        TickTrace {
+            tick: simulation.current_tick(),
+            player: fingerprint(world, simulation.player()),
+            enemies: world.enemies().map(|enemy| fingerprint(world, enemy)).collect(),
+            bullets: world.bullets().map(|bullet| fingerprint(world, bullet)).collect(),
+        }
+    }
+
+    /// A single fingerprint of the whole tick.
+    pub fn hash(&self) -> u64 {
+        let mut fingerprint = Fingerprint::new();
+        fingerprint.u64(self.tick).u64(self.player);
+
+        fingerprint.u64(self.enemies.len() as u64);
+        for enemy in self.enemies.iter() {
+            fingerprint.u64(*enemy);
+        }
+
+        fingerprint.u64(self.bullets.len() as u64);
+        for bullet in self.bullets.iter() {
+            fingerprint.u64(*bullet);
+        }
+
+        fingerprint.finish()
+    }
+
+    /// The first entity that differs from `expected`, if any.
+    pub fn compare(&self, expected: &TickTrace) -> Option<Entity> {
+        if self.player != expected.player {
+            return Some(Entity::Player);
+        }
+
+        if let Some(index) = first_difference(&expected.enemies, &self.enemies) {
+            return Some(Entity::Enemy(index));
+        }
+
+        if self.enemies.len() != expected.enemies.len() {
+            return Some(Entity::EnemyCount { expected: expected.enemies.len(), found: self.enemies.len() });
+        }
+
+        if let Some(index) = first_difference(&expected.bullets, &self.bullets) {
+            return Some(Entity::Bullet(index));
+        }
+
+        if self.bullets.len() != expected.bullets.len() {
+            return Some(Entity::BulletCount { expected: expected.bullets.len(), found: self.bullets.len() });
+        }
+
+        None
+    }
+}
+
+impl Trace {
+    /// Play `replay` through a fresh headless simulation, fingerprinting every tick.
+    pub fn record(replay: &Replay) -> Result<Self, ReplayError> {
+        let ui = UI::new(replay.header.width, replay.header.height);
+        let mut simulation = Simulation::with_stage(ui, replay.header.seed, replay.header.load_stage()?);
+        let mut trace = Trace::default();
+
+        for tick in 0..replay.length() {
+            simulation.tick(replay.actions_at(tick));
+            trace.ticks.push(TickTrace::capture(&simulation));
+        }
+
+        Ok(trace)
+    }
+
+    /// Find the first tick at which `self` stops matching `expected`.
+    pub fn first_divergence(&self, expected: &Trace) -> Option<Divergence> {
+        for (actual, golden) in self.ticks.iter().zip(expected.ticks.iter()) {
+            if let Some(entity) = actual.compare(golden) {
+                return Some(Divergence { tick: golden.tick, entity });
+            }
+        }
+
+        if self.ticks.len() != expected.ticks.len() {
+            let shortest = self.ticks.len().min(expected.ticks.len());
+
+            return Some(Divergence {
+                tick: shortest as u64,
+                entity: Entity::TraceLength { expected: expected.ticks.len(), found: self.ticks.len() },
+            });
+        }
+
+        None
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Trace::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Read a trace in the format written by `Display`, one tick per line:
+    /// `<tick> <hash> player=<fp> enemies=<fp>,.. bullets=<fp>,..`
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut trace = Trace::default();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("line {}: invalid trace", index + 1));
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.len() != 5 {
+                return Err(invalid());
+            }
+
+            let tick_trace = TickTrace {
+                tick: words[0].parse().map_err(|_| invalid())?,
+                player: parse_fingerprints(words[2], "player=").ok_or_else(invalid)?
+                    .first()
+                    .copied()
+                    .ok_or_else(invalid)?,
+                enemies: parse_fingerprints(words[3], "enemies=").ok_or_else(invalid)?,
+                bullets: parse_fingerprints(words[4], "bullets=").ok_or_else(invalid)?,
+            };
+
+            if format!("{:016x}", tick_trace.hash()) != words[1] {
+                return Err(invalid());
+            }
+
+            trace.ticks.push(tick_trace);
+        }
+
+        Ok(trace)
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for tick in self.ticks.iter() {
+            writeln!(
+                f,
+                "{} {:016x} player={:016x} enemies={} bullets={}",
+                tick.tick,
+                tick.hash(),
+                tick.player,
+                join_fingerprints(&tick.enemies),
+                join_fingerprints(&tick.bullets),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "diverged at tick {}: ", self.tick)?;
+
+        match &self.entity {
+            Entity::Player => write!(f, "player differs"),
+            Entity::Enemy(index) => write!(f, "enemy {} differs", index),
+            Entity::Bullet(index) => write!(f, "bullet {} differs", index),
+            Entity::EnemyCount { expected, found } => {
+                write!(f, "expected {} enemies, found {}", expected, found)
+            },
+            Entity::BulletCount { expected, found } => {
+                write!(f, "expected {} bullets, found {}", expected, found)
+            },
+            Entity::TraceLength { expected, found } => {
+                write!(f, "expected {} ticks, found {}", expected, found)
+            },
+        }
+    }
+}
+
+fn first_difference(expected: &[u64], actual: &[u64]) -> Option<usize> {
+    expected.iter()
+        .zip(actual.iter())
+        .position(|(expected, actual)| expected != actual)
+}
+
+fn join_fingerprints(fingerprints: &[u64]) -> String {
+    if fingerprints.is_empty() {
+        return "-".to_string();
+    }
+
+    fingerprints.iter()
+        .map(|fingerprint| format!("{:016x}", fingerprint))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_fingerprints(word: &str, prefix: &str) -> Option<Vec<u64>> {
+    let list = word.strip_prefix(prefix)?;
+    if list == "-" {
+        return Some(vec![]);
+    }
+
+    list.split(',')
+        .map(|fingerprint| u64::from_str_radix(fingerprint, 16).ok())
+        .collect()
+}
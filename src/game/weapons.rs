@@ -0,0 +1,103 @@
+use crate::{
+    game::{
+        bullets,
+        patterns::{Emitter, Target},
+        rng::Rng,
+    },
+    physics::{
+        motion::{Offset, Position},
+        units::Pixels,
+    },
+};
+
+use chrono::Duration;
+
+
+/// A weapon the player fires: one or more streams of bullets, all fired together
+/// once every `cooldown` for as long as the trigger is held.
+#[derive(Clone, Debug)]
+pub struct Weapon {
+    pub streams: Vec<Stream>,
+    /// How long to wait between volleys.
+    pub cooldown: Duration,
+
+    /// How long until the weapon can fire again. Below zero when a volley is
+    /// overdue, so that the rate of fire doesn't drift with the tick length.
+    ready_in: Duration,
+}
+
+/// One stream of a weapon's fire.
+#[derive(Clone, Debug)]
+pub struct Stream {
+    pub emitter: Emitter,
+    /// Where the stream's bullets are centred when fired, relative to the shooter's position.
+    pub muzzle: Offset<Pixels>,
+}
+
+impl Weapon {
+    /// A weapon with no streams yet, firing once every `cooldown`.
+    pub fn new(cooldown: Duration) -> Self {
+        Weapon {
+            streams: vec![],
+            cooldown,
+            ready_in: Duration::zero(),
+        }
+    }
+
+    /// Add a stream firing `emitter`'s pattern from `muzzle`.
+    pub fn with_stream(mut self, emitter: Emitter, muzzle: Offset<Pixels>) -> Self {
+        self.streams.push(Stream { emitter, muzzle });
+        self
+    }
+
+    /// Whether the weapon has cooled down since its last volley.
+    pub fn ready(&self) -> bool {
+        self.ready_in <= Duration::zero()
+    }
+
+    /// Fire a volley from every stream of a shooter at `position` right away, and
+    /// start cooling down.
+    pub fn fire(&mut self, position: Position<Pixels>, target: Option<Target>, rng: &mut Rng) -> Vec<bullets::Bullet> {
+        self.ready_in = self.ready_in.max(Duration::zero()) + self.cooldown;
+
+        self.streams
+            .iter_mut()
+            .flat_map(|stream| stream.fire(position, target, rng))
+            .collect()
+    }
+
+    /// Fire if the trigger is `held` and the weapon is ready, then let it cool down for `time`.
+    pub fn update(
+        &mut self,
+        time: Duration,
+        held: bool,
+        position: Position<Pixels>,
+        target: Option<Target>,
+        rng: &mut Rng,
+    ) -> Vec<bullets::Bullet> {
+        let volley = if held && self.ready() {
+            self.ready_in += self.cooldown;
+
+            self.streams
+                .iter_mut()
+                .flat_map(|stream| stream.fire(position, target, rng))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        self.ready_in -= time;
+        if !held {
+            self.ready_in = self.ready_in.max(Duration::zero());
+        }
+
+        volley
+    }
+}
+
+impl Stream {
+    /// Fire the stream's pattern from a shooter at `position`, centring each bullet on the muzzle.
+    pub fn fire(&mut self, position: Position<Pixels>, target: Option<Target>, rng: &mut Rng) -> Vec<bullets::Bullet> {
+        self.emitter.fire(position + self.muzzle, target, rng)
+    }
+}
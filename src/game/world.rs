@@ -0,0 +1,270 @@
+//! A small, hand-rolled entity-component-system.
+//!
+//! Every game object is an `Entity`, a handle with no data of its own, and its data
+//! is split into components kept in one storage per type in the `World`. What an
+//! entity is depends only on which components it has: anything with a `Pilot` is
+//! the player, anything with an `Armament` an enemy, anything with a `Projectile`
+//! a bullet, anything with a `Pickup` an item and anything with a `Satellite` one of
+//! the player's drones. The systems in `systems` each work through the entities with the
+//! components they need.
+
+use crate::{
+    game::{
+        arena::{Arena, Handle},
+        bombs::Bombs,
+        boss::Phases,
+        bullets::Projectile,
+        components::{Faction, Hitbox, Renderable, Transform, Velocity},
+        drones::{Satellite, Trail},
+        enemies::{Armament, Reward},
+        health::HealthPoints,
+        items::Pickup,
+        lives::Lives,
+        paths::Follower,
+        player::Pilot,
+    },
+    physics::collision,
+};
+
+
+/// A game object, which is whatever components the `World` holds for it. Entities
+/// that have been despawned go stale like any other `Handle`.
+pub type Entity = Handle;
+
+/// Storage for one type of component, indexed by entity.
+///
+/// Components are kept in the order of their entities' slots, so iterating over
+/// them always visits entities in the same order, whichever storage it is done through.
+#[derive(Clone, Debug)]
+pub struct Components<T> {
+    slots: Vec<Option<(Entity, T)>>,
+}
+
+/// A group of components that make up one sort of game object, spawned together.
+pub trait Bundle {
+    /// Give `entity` each of the bundle's components.
+    fn insert(self, world: &mut World, entity: Entity);
+}
+
+/// Every entity in the game and all of their components.
+pub struct World {
+    entities: Arena<()>,
+
+    pub transforms: Components<Transform>,
+    pub velocities: Components<Velocity>,
+    pub routes: Components<Follower>,
+    pub hitboxes: Components<Hitbox>,
+    pub healths: Components<HealthPoints>,
+    pub factions: Components<Faction>,
+    pub renderables: Components<Renderable>,
+
+    pub pilots: Components<Pilot>,
+    pub lives: Components<Lives>,
+    pub bombs: Components<Bombs>,
+    pub trails: Components<Trail>,
+    pub satellites: Components<Satellite>,
+    pub armaments: Components<Armament>,
+    pub rewards: Components<Reward>,
+    pub bosses: Components<Phases>,
+    pub projectiles: Components<Projectile>,
+    pub pickups: Components<Pickup>,
+}
+
+impl<T> Components<T> {
+    pub fn new() -> Self {
+        Components { slots: vec![] }
+    }
+
+    /// Storage with room for the components of `capacity` entities before it needs to allocate more.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Components { slots: Vec::with_capacity(capacity) }
+    }
+
+    /// Give `entity` a component, replacing any it already had of this type.
+    pub fn insert(&mut self, entity: Entity, component: T) {
+        let index = entity.index();
+
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+
+        self.slots[index] = Some((entity, component));
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let slot = self.slots.get_mut(entity.index())?;
+
+        match slot {
+            Some((owner, _)) if *owner == entity => slot.take().map(|(_, component)| component),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        match self.slots.get(entity.index()) {
+            Some(Some((owner, component))) if *owner == entity => Some(component),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        match self.slots.get_mut(entity.index()) {
+            Some(Some((owner, component))) if *owner == entity => Some(component),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.get(entity).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.slots.iter().filter_map(|slot| slot.as_ref().map(|(entity, component)| (*entity, component)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.slots.iter_mut().filter_map(|slot| slot.as_mut().map(|(entity, component)| (*entity, component)))
+    }
+
+    /// Every entity with this type of component.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.iter().map(|(entity, _)| entity)
+    }
+}
+
+impl<T> Default for Components<T> {
+    fn default() -> Self {
+        Components::new()
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        World::with_capacity(0)
+    }
+
+    /// A world with room for `capacity` entities before any of its storage has to grow.
+    pub fn with_capacity(capacity: usize) -> Self {
+        World {
+            entities: Arena::with_capacity(capacity),
+            transforms: Components::with_capacity(capacity),
+            velocities: Components::with_capacity(capacity),
+            routes: Components::with_capacity(capacity),
+            hitboxes: Components::with_capacity(capacity),
+            healths: Components::with_capacity(capacity),
+            factions: Components::with_capacity(capacity),
+            renderables: Components::with_capacity(capacity),
+            pilots: Components::new(),
+            lives: Components::new(),
+            bombs: Components::new(),
+            trails: Components::new(),
+            satellites: Components::new(),
+            armaments: Components::with_capacity(capacity),
+            rewards: Components::with_capacity(capacity),
+            bosses: Components::new(),
+            projectiles: Components::with_capacity(capacity),
+            pickups: Components::new(),
+        }
+    }
+
+    /// How many entities the world can hold before its storage has to grow.
+    pub fn capacity(&self) -> usize {
+        self.entities.capacity()
+    }
+
+    /// How many entities there are.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Add a new entity made up of `bundle`'s components.
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let entity = self.entities.insert(());
+        bundle.insert(self, entity);
+        entity
+    }
+
+    /// Remove `entity` and all of its components, returning whether it was still there.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if self.entities.remove(entity).is_none() {
+            return false;
+        }
+
+        self.transforms.remove(entity);
+        self.velocities.remove(entity);
+        self.routes.remove(entity);
+        self.hitboxes.remove(entity);
+        self.healths.remove(entity);
+        self.factions.remove(entity);
+        self.renderables.remove(entity);
+        self.pilots.remove(entity);
+        self.lives.remove(entity);
+        self.bombs.remove(entity);
+        self.trails.remove(entity);
+        self.satellites.remove(entity);
+        self.armaments.remove(entity);
+        self.rewards.remove(entity);
+        self.bosses.remove(entity);
+        self.projectiles.remove(entity);
+        self.pickups.remove(entity);
+
+        true
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(entity)
+    }
+
+    /// The entity in slot `index`, if any, e.g. to turn a `spatial::Grid` result back into an entity.
+    pub fn entity_at(&self, index: usize) -> Option<Entity> {
+        self.entities.handle_at(index)
+    }
+
+    /// The part of `entity` that can hit or be hit, wherever it has moved to.
+    pub fn hitbox(&self, entity: Entity) -> Option<collision::Shape> {
+        let transform = self.transforms.get(entity)?;
+
+        self.hitboxes.get(entity).map(|hitbox| hitbox.at(transform.position))
+    }
+
+    pub fn health(&self, entity: Entity) -> Option<HealthPoints> {
+        self.healths.get(entity).copied()
+    }
+
+    /// Every enemy, in the order they are stored.
+    pub fn enemies(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.armaments.entities()
+    }
+
+    /// Every bullet, in the order they are stored.
+    pub fn bullets(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.projectiles.entities()
+    }
+
+    /// Every item waiting to be collected, in the order they are stored.
+    pub fn items(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.pickups.entities()
+    }
+
+    /// Every drone flying with `owner`, in the order they are stored.
+    pub fn drones(&self, owner: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.satellites.iter()
+            .filter(move |(_, satellite)| satellite.owner() == owner)
+            .map(|(drone, _)| drone)
+    }
+
+    /// The boss currently being fought, if any.
+    pub fn boss(&self) -> Option<Entity> {
+        self.bosses.entities().next()
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        World::new()
+    }
+}
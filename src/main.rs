@@ -1,27 +1,85 @@
-mod config;
-mod game;
-mod physics;
-
-use game::{
-    state::State,
-    player::Player,
+use glhf::{
+    config::ui::UI,
+    game::{
+        replay::{
+            Header,
+            Playback,
+            Recorder,
+            Replay,
+        },
+        stage::Stage,
+        state::{
+            Mode,
+            State,
+        },
+    },
 };
-use config::ui::UI;
 
-//use ggez::{
-//    graphics,
-//    Context,
-//    GameResult,
-//};
+use std::env;
+
+use chrono::offset::Utc;
+use ggez::GameError;
+
+
+const USAGE: &str = "usage: glhf [--seed N] [--stage PATH] [--record PATH | --replay PATH]";
 
 fn main() -> ggez::GameResult {
     let config = UI::default();
+    let (seed, stage, mode) = parse_args(&config)?;
+
     let (ctx, events_loop) = ggez::ContextBuilder::new("Shooter", "Arcadia Rose <fleurdarcadia@protonmail.com>")
         .window_setup(ggez::conf::WindowSetup::default().title("Shooter"))
         .window_mode(ggez::conf::WindowMode::default().dimensions(config.width, config.height))
         .build()?;
 
-    let state = State::new(config);
+    let state = State::new(config, seed, stage, mode);
 
     ggez::event::run(ctx, events_loop, state);
 }
+
+/// Work out the seed, stage and input mode from the command line.
+/// Replays bring their own seed and stage.
+fn parse_args(config: &UI) -> Result<(u64, Stage, Mode), GameError> {
+    let mut seed = Utc::now().timestamp_nanos_opt().unwrap_or(0) as u64;
+    let mut stage = None;
+    let mut record = None;
+    let mut replay = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| GameError::CustomError(USAGE.to_string()));
+
+        match arg.as_str() {
+            "--seed"   => seed = value()?.parse().map_err(|_| GameError::CustomError(USAGE.to_string()))?,
+            "--stage"  => stage = Some(value()?),
+            "--record" => record = Some(value()?),
+            "--replay" => replay = Some(value()?),
+            _          => return Err(GameError::CustomError(USAGE.to_string())),
+        }
+    }
+
+    let mut header = Header::new(seed, config);
+    if let Some(stage) = stage {
+        header = header.with_stage(&stage)
+            .map_err(|err| GameError::CustomError(format!("{}: {}", stage, err)))?;
+    }
+
+    let mode = match (record, replay) {
+        (Some(_), Some(_)) => return Err(GameError::CustomError(USAGE.to_string())),
+        (Some(path), None) => Mode::Record(Recorder::new(header.clone(), path)),
+        (None, Some(path)) => {
+            let replay = Replay::load(&path)
+                .and_then(|replay| replay.header.verify(config).map(|_| replay))
+                .map_err(|err| GameError::CustomError(format!("{}: {}", path, err)))?;
+
+            header = replay.header.clone();
+            Mode::Replay(Playback::new(replay))
+        },
+        (None, None) => Mode::Live,
+    };
+
+    let stage = header.load_stage()
+        .map_err(|err| GameError::CustomError(format!("{}: {}", header.stage.as_deref().unwrap_or("stage"), err)))?;
+
+    Ok((header.seed, stage, mode))
+}
@@ -0,0 +1,270 @@
+use super::units::Radians;
+
+
+/// An axis-aligned rectangle used to test whether two objects touch.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Rect { x, y, w, h }
+    }
+
+    pub fn left(&self) -> f32 {
+        self.x
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.w
+    }
+
+    pub fn top(&self) -> f32 {
+        self.y
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.h
+    }
+
+    pub fn centre(&self) -> Point {
+        (self.x + self.w / 2.0, self.y + self.h / 2.0)
+    }
+
+    /// Whether the two rectangles share any area, edges included.
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        self.left() <= other.right()
+            && self.right() >= other.left()
+            && self.top() <= other.bottom()
+            && self.bottom() >= other.top()
+    }
+}
+
+/// A point in pixels, as used by `Shape`.
+pub type Point = (f32, f32);
+
+/// The outline of something that can be hit, which need not match the size or
+/// shape it is drawn at.
+///
+/// Shapes are usually described relative to the top left corner of whatever they
+/// belong to, and `translated` to wherever that is before testing for a hit.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Shape {
+    Rect(Rect),
+    Circle { centre: Point, radius: f32 },
+    /// Every point within `radius` of the line from `from` to `to`, e.g. a laser.
+    Capsule { from: Point, to: Point, radius: f32 },
+    /// A rectangle turned clockwise about its centre by `angle`.
+    RotatedRect { centre: Point, half_extents: Point, angle: Radians },
+}
+
+/// A rectangle in terms of its centre and its own axes, which every rectangular
+/// shape is turned into for testing.
+struct OrientedRect {
+    centre: Point,
+    half_extents: Point,
+    axes: [Point; 2],
+}
+
+impl Shape {
+    /// The same shape moved by `(x, y)`.
+    pub fn translated(&self, x: f32, y: f32) -> Shape {
+        let shift = |(px, py): Point| (px + x, py + y);
+
+        match *self {
+            Shape::Rect(rect) => Shape::Rect(Rect::new(rect.x + x, rect.y + y, rect.w, rect.h)),
+            Shape::Circle { centre, radius } => Shape::Circle { centre: shift(centre), radius },
+            Shape::Capsule { from, to, radius } => Shape::Capsule { from: shift(from), to: shift(to), radius },
+            Shape::RotatedRect { centre, half_extents, angle } => {
+                Shape::RotatedRect { centre: shift(centre), half_extents, angle }
+            },
+        }
+    }
+
+    /// The smallest axis-aligned rectangle the shape fits in.
+    pub fn bounds(&self) -> Rect {
+        match *self {
+            Shape::Rect(rect) => rect,
+            Shape::Circle { centre: (x, y), radius } => {
+                Rect::new(x - radius, y - radius, radius * 2.0, radius * 2.0)
+            },
+            Shape::Capsule { from, to, radius } => {
+                let left = from.0.min(to.0) - radius;
+                let top = from.1.min(to.1) - radius;
+
+                Rect::new(left, top, (from.0 - to.0).abs() + radius * 2.0, (from.1 - to.1).abs() + radius * 2.0)
+            },
+            Shape::RotatedRect { centre: (x, y), half_extents: (half_width, half_height), angle } => {
+                let (sin, cos) = angle.value().sin_cos();
+                let extent_x = cos.abs() * half_width + sin.abs() * half_height;
+                let extent_y = sin.abs() * half_width + cos.abs() * half_height;
+
+                Rect::new(x - extent_x, y - extent_y, extent_x * 2.0, extent_y * 2.0)
+            },
+        }
+    }
+
+    /// Whether the two shapes share any area, edges included.
+    pub fn overlaps(&self, other: &Shape) -> bool {
+        match (*self, *other) {
+            (Shape::Rect(a), Shape::Rect(b)) => a.overlaps(&b),
+
+            (Shape::Circle { centre: a, radius: ra }, Shape::Circle { centre: b, radius: rb }) => {
+                distance_squared(a, b) <= (ra + rb) * (ra + rb)
+            },
+            (Shape::Circle { centre, radius: rc }, Shape::Capsule { from, to, radius: rs }) => {
+                distance_squared(centre, closest_on_segment(centre, from, to)) <= (rc + rs) * (rc + rs)
+            },
+            (Shape::Circle { centre, radius }, rectangle @ (Shape::Rect(_) | Shape::RotatedRect { .. })) => {
+                let rectangle = rectangle.oriented_rect().expect("only rectangles are left");
+                let (x, y) = rectangle.to_local(centre);
+                let (half_width, half_height) = rectangle.half_extents;
+
+                distance_squared((x, y), (x.clamp(-half_width, half_width), y.clamp(-half_height, half_height)))
+                    <= radius * radius
+            },
+
+            (Shape::Capsule { from: a, to: b, radius: ra }, Shape::Capsule { from: c, to: d, radius: rb }) => {
+                segment_distance_squared(a, b, c, d) <= (ra + rb) * (ra + rb)
+            },
+            (Shape::Capsule { from, to, radius }, rectangle @ (Shape::Rect(_) | Shape::RotatedRect { .. })) => {
+                let rectangle = rectangle.oriented_rect().expect("only rectangles are left");
+                let (from, to) = (rectangle.to_local(from), rectangle.to_local(to));
+
+                rectangle.contains_local(from)
+                    || rectangle.contains_local(to)
+                    || rectangle
+                        .local_edges()
+                        .iter()
+                        .any(|&(c, d)| segment_distance_squared(from, to, c, d) <= radius * radius)
+            },
+
+            (a @ (Shape::Rect(_) | Shape::RotatedRect { .. }), b @ (Shape::Rect(_) | Shape::RotatedRect { .. })) => {
+                let (a, b) = (a.oriented_rect().unwrap(), b.oriented_rect().unwrap());
+
+                a.axes.iter().chain(b.axes.iter()).all(|&axis| {
+                    let (a_min, a_max) = a.project(axis);
+                    let (b_min, b_max) = b.project(axis);
+
+                    a_min <= b_max && b_min <= a_max
+                })
+            },
+
+            // Every other pair is one of the above the other way round.
+            (a, b) => b.overlaps(&a),
+        }
+    }
+
+    fn oriented_rect(&self) -> Option<OrientedRect> {
+        match *self {
+            Shape::Rect(rect) => Some(OrientedRect {
+                centre: (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0),
+                half_extents: (rect.w / 2.0, rect.h / 2.0),
+                axes: [(1.0, 0.0), (0.0, 1.0)],
+            }),
+            Shape::RotatedRect { centre, half_extents, angle } => {
+                let (sin, cos) = angle.value().sin_cos();
+
+                Some(OrientedRect { centre, half_extents, axes: [(cos, sin), (-sin, cos)] })
+            },
+            _ => None,
+        }
+    }
+}
+
+impl From<Rect> for Shape {
+    fn from(rect: Rect) -> Self {
+        Shape::Rect(rect)
+    }
+}
+
+impl OrientedRect {
+    /// Where `point` is relative to the rectangle's centre, along its own axes.
+    fn to_local(&self, point: Point) -> Point {
+        let offset = (point.0 - self.centre.0, point.1 - self.centre.1);
+
+        (dot(offset, self.axes[0]), dot(offset, self.axes[1]))
+    }
+
+    fn contains_local(&self, (x, y): Point) -> bool {
+        x.abs() <= self.half_extents.0 && y.abs() <= self.half_extents.1
+    }
+
+    /// The rectangle's sides, relative to its centre and along its own axes.
+    fn local_edges(&self) -> [(Point, Point); 4] {
+        let (w, h) = self.half_extents;
+
+        [
+            ((-w, -h), (w, -h)),
+            ((w, -h), (w, h)),
+            ((w, h), (-w, h)),
+            ((-w, h), (-w, -h)),
+        ]
+    }
+
+    /// The range the rectangle covers along `axis`.
+    fn project(&self, axis: Point) -> (f32, f32) {
+        let centre = dot(self.centre, axis);
+        let extent = self.half_extents.0 * dot(self.axes[0], axis).abs()
+            + self.half_extents.1 * dot(self.axes[1], axis).abs();
+
+        (centre - extent, centre + extent)
+    }
+}
+
+fn dot(a: Point, b: Point) -> f32 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn cross(a: Point, b: Point) -> f32 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn minus(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn distance_squared(a: Point, b: Point) -> f32 {
+    let offset = minus(a, b);
+
+    dot(offset, offset)
+}
+
+/// The point on the line from `from` to `to` nearest to `point`.
+fn closest_on_segment(point: Point, from: Point, to: Point) -> Point {
+    let along = minus(to, from);
+    let length_squared = dot(along, along);
+
+    if length_squared == 0.0 {
+        return from;
+    }
+
+    let t = (dot(minus(point, from), along) / length_squared).clamp(0.0, 1.0);
+
+    (from.0 + along.0 * t, from.1 + along.1 * t)
+}
+
+/// The square of the shortest distance between the lines from `a` to `b` and from `c` to `d`.
+fn segment_distance_squared(a: Point, b: Point, c: Point, d: Point) -> f32 {
+    let side = |point: Point, from: Point, to: Point| cross(minus(to, from), minus(point, from));
+
+    let (c_side, d_side) = (side(c, a, b), side(d, a, b));
+    let (a_side, b_side) = (side(a, c, d), side(b, c, d));
+
+    if c_side * d_side < 0.0 && a_side * b_side < 0.0 {
+        return 0.0;
+    }
+
+    [
+        distance_squared(a, closest_on_segment(a, c, d)),
+        distance_squared(b, closest_on_segment(b, c, d)),
+        distance_squared(c, closest_on_segment(c, a, b)),
+        distance_squared(d, closest_on_segment(d, a, b)),
+    ]
+    .into_iter()
+    .fold(f32::INFINITY, f32::min)
+}
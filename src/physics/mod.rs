@@ -1,2 +1,4 @@
+pub mod collision;
 pub mod motion;
+pub mod spatial;
 pub mod units;
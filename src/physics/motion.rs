@@ -1,25 +1,39 @@
-use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
 
-use super::units;
+use super::units::{
+    self,
+    Pixels,
+    PixelsPerMs,
+    PixelsPerMsSquared,
+    Radians,
+};
 
 use chrono::Duration;
-use ggez::event::KeyCode;
 
+use serde::Deserialize;
 
-#[derive(Copy, Clone)]
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Position<U: Copy>{
     pub x: U,
     pub y: U,
 }
 
-#[derive(Copy, Clone)]
+/// The difference between two positions.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Offset<U: Copy> {
+    pub x: U,
+    pub y: U,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Dimensions<U: Copy> {
     pub width: U,
     pub height: U,
 }
 
 /// An option-like representation of the directions of arrow keys.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
@@ -28,19 +42,51 @@ pub enum Direction {
     Stationary,
 }
 
-/// Represents directional motion in some specified units.
-pub struct Velocity<U>(f32, PhantomData<U>);
+/// Represents directional motion in some specified units, split into horizontal
+/// and vertical components.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Velocity<U: Copy> {
+    pub x: U,
+    pub y: U,
+}
+
+/// How quickly a velocity changes, split into horizontal and vertical components.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Acceleration<U: Copy> {
+    pub x: U,
+    pub y: U,
+}
+
+/// How quickly a velocity turns.
+pub type AngularVelocity = units::RadiansPerMs;
+
+/// Everything about how an object moves: a velocity that is changed by an
+/// acceleration and turned by an angular velocity as time passes.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Kinematics {
+    pub velocity: Velocity<PixelsPerMs>,
+    pub acceleration: Acceleration<PixelsPerMsSquared>,
+    pub angular_velocity: AngularVelocity,
+}
+
+/// Shapes how a transition progresses from start to finish.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// Starts slowly and speeds up.
+    EaseIn,
+    /// Starts quickly and slows down.
+    EaseOut,
+    /// Starts and finishes slowly.
+    EaseInOut,
+}
 
 pub trait Object<U: Copy> {
     fn position(&self) -> Position<U>;
     fn dimensions(&self) -> Dimensions<U>;
 }
 
-pub trait Acceleration<U> {
-    fn horizontal_velocity(&self, time: Duration) -> Velocity<U>;
-    fn vertical_velocity(&self, time: Duration) -> Velocity<U>;
-}
-
 impl Direction {
     pub fn is_horizontal(&self) -> bool {
         *self == Direction::Left || *self == Direction::Right
@@ -52,31 +98,262 @@ impl Direction {
 }
 
 impl<U: Copy> Velocity<U> {
-    pub fn new(value: f32) -> Self {
-        Velocity(value, PhantomData)
+    pub fn new(x: U, y: U) -> Self {
+        Velocity {
+            x,
+            y,
+        }
     }
 }
 
-impl Velocity<units::PixelsPerMs> {
-    pub fn distance(&self, time: Duration) -> units::Pixels {
-        units::Pixels(self.0 * time.num_milliseconds() as f32)
+impl Velocity<PixelsPerMs> {
+    pub fn zero() -> Self {
+        Velocity::new(PixelsPerMs(0.0), PixelsPerMs(0.0))
+    }
+
+    /// A velocity of `speed` heading `angle` clockwise from the positive x axis.
+    pub fn from_angle<A: Into<Radians>>(angle: A, speed: PixelsPerMs) -> Self {
+        let (sin, cos) = angle.into().sin_cos();
+
+        Velocity::new(speed * cos, speed * sin)
+    }
+
+    pub fn speed(&self) -> PixelsPerMs {
+        PixelsPerMs(self.x.value().hypot(self.y.value()))
+    }
+
+    /// The heading of the velocity clockwise from the positive x axis.
+    pub fn angle(&self) -> Radians {
+        Radians(self.y.value().atan2(self.x.value()))
+    }
+
+    /// The same velocity turned clockwise by `angle`.
+    pub fn rotated<A: Into<Radians>>(&self, angle: A) -> Self {
+        let (sin, cos) = angle.into().sin_cos();
+
+        Velocity::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Linearly interpolate towards `other`, where a `t` of 0.0 is `self` and 1.0 is `other`.
+    pub fn lerp(&self, other: Velocity<PixelsPerMs>, t: f32) -> Self {
+        Velocity::new(self.x + (other.x - self.x) * t, self.y + (other.y - self.y) * t)
+    }
+}
+
+impl Add for Velocity<PixelsPerMs> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Velocity::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Mul<f32> for Velocity<PixelsPerMs> {
+    type Output = Self;
+
+    fn mul(self, scale: f32) -> Self {
+        Velocity::new(self.x * scale, self.y * scale)
+    }
+}
+
+/// How far an object moving at this velocity travels in some amount of time.
+impl Mul<Duration> for Velocity<PixelsPerMs> {
+    type Output = Offset<Pixels>;
+
+    fn mul(self, time: Duration) -> Offset<Pixels> {
+        Offset::new(self.x * time, self.y * time)
+    }
+}
+
+/// The velocity that covers an offset in some amount of time.
+impl Div<Duration> for Offset<Pixels> {
+    type Output = Velocity<PixelsPerMs>;
+
+    fn div(self, time: Duration) -> Velocity<PixelsPerMs> {
+        Velocity::new(self.x / time, self.y / time)
+    }
+}
+
+impl<U: Copy> Acceleration<U> {
+    pub fn new(x: U, y: U) -> Self {
+        Acceleration {
+            x,
+            y,
+        }
+    }
+}
+
+impl Acceleration<PixelsPerMsSquared> {
+    pub fn zero() -> Self {
+        Acceleration::new(PixelsPerMsSquared(0.0), PixelsPerMsSquared(0.0))
+    }
+}
+
+/// How much a velocity changes under this acceleration in some amount of time.
+impl Mul<Duration> for Acceleration<PixelsPerMsSquared> {
+    type Output = Velocity<PixelsPerMs>;
+
+    fn mul(self, time: Duration) -> Velocity<PixelsPerMs> {
+        Velocity::new(self.x * time, self.y * time)
+    }
+}
+
+impl Kinematics {
+    /// Moving at a constant velocity.
+    pub fn new(velocity: Velocity<PixelsPerMs>) -> Self {
+        Kinematics {
+            velocity,
+            acceleration: Acceleration::zero(),
+            angular_velocity: AngularVelocity::default(),
+        }
+    }
+
+    pub fn stationary() -> Self {
+        Kinematics::new(Velocity::zero())
+    }
+
+    pub fn with_acceleration(mut self, acceleration: Acceleration<PixelsPerMsSquared>) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    pub fn with_angular_velocity(mut self, angular_velocity: AngularVelocity) -> Self {
+        self.angular_velocity = angular_velocity;
+        self
+    }
+
+    /// Advance the motion by `time`, returning how far the object moved while the
+    /// velocity is accelerated and turned along the way.
+    pub fn integrate(&mut self, time: Duration) -> Offset<Pixels> {
+        // Constant acceleration over the step contributes half of a * t^2.
+        let change_in_velocity = self.acceleration * time;
+        let offset = self.velocity * time + change_in_velocity * time * 0.5;
+
+        self.velocity = self.velocity + change_in_velocity;
+
+        if self.angular_velocity.value() != 0.0 {
+            self.velocity = self.velocity.rotated(self.angular_velocity * time);
+        }
+
+        offset
+    }
+}
+
+impl Easing {
+    /// Map linear progress `t` from 0.0 to 1.0 onto this easing curve.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear    => t,
+            Easing::EaseIn    => t * t,
+            Easing::EaseOut   => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
     }
 }
 
 impl<U: Copy> Position<U> {
     pub fn new(x: U, y: U) -> Self {
         Position {
-            x: x,
-            y: y,
+            x,
+            y,
+        }
+    }
+}
+
+impl Position<Pixels> {
+    /// Linearly interpolate towards `other`, where an `alpha` of 0.0 is `self` and 1.0 is `other`.
+    pub fn lerp(&self, other: Position<Pixels>, alpha: f32) -> Self {
+        *self + (other - *self) * alpha
+    }
+}
+
+impl Add<Offset<Pixels>> for Position<Pixels> {
+    type Output = Self;
+
+    fn add(self, offset: Offset<Pixels>) -> Self {
+        Position::new(self.x + offset.x, self.y + offset.y)
+    }
+}
+
+impl Sub<Offset<Pixels>> for Position<Pixels> {
+    type Output = Self;
+
+    fn sub(self, offset: Offset<Pixels>) -> Self {
+        Position::new(self.x - offset.x, self.y - offset.y)
+    }
+}
+
+impl Sub for Position<Pixels> {
+    type Output = Offset<Pixels>;
+
+    fn sub(self, other: Self) -> Offset<Pixels> {
+        Offset::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<U: Copy> Offset<U> {
+    pub fn new(x: U, y: U) -> Self {
+        Offset {
+            x,
+            y,
         }
     }
 }
 
+impl Offset<Pixels> {
+    pub fn zero() -> Self {
+        Offset::new(Pixels(0.0), Pixels(0.0))
+    }
+
+    pub fn length(&self) -> Pixels {
+        Pixels(self.x.value().hypot(self.y.value()))
+    }
+
+    /// The direction of the offset clockwise from the positive x axis.
+    pub fn angle(&self) -> Radians {
+        Radians(self.y.value().atan2(self.x.value()))
+    }
+}
+
+impl Add for Offset<Pixels> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Offset::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Offset<Pixels> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Offset::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Offset<Pixels> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Offset::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<f32> for Offset<Pixels> {
+    type Output = Self;
+
+    fn mul(self, scale: f32) -> Self {
+        Offset::new(self.x * scale, self.y * scale)
+    }
+}
+
 impl<U: Copy> Dimensions<U> {
     pub fn new(width: U, height: U) -> Self {
         Dimensions {
-            width: width,
-            height: height,
+            width,
+            height,
         }
     }
 }
@@ -0,0 +1,99 @@
+use super::collision::Rect;
+
+
+/// A broad-phase index that buckets rectangles into a uniform grid of square cells,
+/// so that finding what might touch a rectangle only means looking at the handful
+/// of cells it covers rather than at everything.
+///
+/// The grid covers a fixed area, usually the screen. Anything outside it is kept in
+/// the cells along its edge, so it is still found, just less quickly. Rectangles are
+/// stored by index, e.g. into the `Vec` they came from, and the whole grid is meant to
+/// be cleared and refilled every tick.
+#[derive(Clone, Debug)]
+pub struct Grid {
+    bounds: Rect,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    /// An empty grid over `bounds` with cells `cell_size` pixels across. Cells a little
+    /// larger than the things stored in them work best.
+    pub fn new(bounds: Rect, cell_size: f32) -> Self {
+        let columns = (bounds.w / cell_size).ceil().max(1.0) as usize;
+        let rows = (bounds.h / cell_size).ceil().max(1.0) as usize;
+
+        Grid {
+            bounds,
+            cell_size,
+            columns,
+            rows,
+            cells: vec![vec![]; columns * rows],
+        }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Empty every cell, keeping their storage around for the next tick.
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+    }
+
+    /// Store `index` in every cell `rect` covers.
+    pub fn insert(&mut self, index: usize, rect: &Rect) {
+        let (columns, rows) = self.cells_covering(rect);
+
+        for row in rows {
+            for column in columns.clone() {
+                self.cells[row * self.columns + column].push(index);
+            }
+        }
+    }
+
+    /// Replace the contents with `rects`, each stored under its position in the iterator.
+    pub fn rebuild<I: IntoIterator<Item = Rect>>(&mut self, rects: I) {
+        self.clear();
+
+        for (index, rect) in rects.into_iter().enumerate() {
+            self.insert(index, &rect);
+        }
+    }
+
+    /// Fill `candidates` with the index of everything sharing a cell with `rect`,
+    /// in ascending order and without duplicates. These might touch `rect`; anything
+    /// else certainly doesn't.
+    pub fn query(&self, rect: &Rect, candidates: &mut Vec<usize>) {
+        candidates.clear();
+
+        let (columns, rows) = self.cells_covering(rect);
+        for row in rows {
+            for column in columns.clone() {
+                candidates.extend_from_slice(&self.cells[row * self.columns + column]);
+            }
+        }
+
+        candidates.sort_unstable();
+        candidates.dedup();
+    }
+
+    /// The columns and rows of the cells `rect` covers, edges included to match
+    /// `Rect::overlaps`, and clamped to the grid.
+    fn cells_covering(&self, rect: &Rect) -> (std::ops::RangeInclusive<usize>, std::ops::RangeInclusive<usize>) {
+        let cell = |offset: f32, count: usize| ((offset / self.cell_size).floor().max(0.0) as usize).min(count - 1);
+
+        let columns = cell(rect.left() - self.bounds.left(), self.columns)..=cell(rect.right() - self.bounds.left(), self.columns);
+        let rows = cell(rect.top() - self.bounds.top(), self.rows)..=cell(rect.bottom() - self.bounds.top(), self.rows);
+
+        (columns, rows)
+    }
+}
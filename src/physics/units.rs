@@ -1,15 +1,197 @@
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use chrono::Duration;
+
+
 /// A unit of distance.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
 pub struct Pixels(pub f32);
 
 /// A unit representing the number of pixels that an object moves across the screen
 /// per millisecond between updates.
-#[derive(Copy, Clone)]
-pub struct PixelsPerMs;
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct PixelsPerMs(pub f32);
+
+/// A unit representing how much a speed in `PixelsPerMs` changes every millisecond.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct PixelsPerMsSquared(pub f32);
+
+/// A unit of angle. Angles grow clockwise on screen, since the y axis points down.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Radians(pub f32);
+
+/// A unit of angle that is friendlier to write by hand than `Radians`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Degrees(pub f32);
+
+/// A unit representing how far something turns per millisecond.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct RadiansPerMs(pub f32);
+
+/// Implements the arithmetic that keeps a quantity in the same unit: adding and
+/// subtracting quantities, scaling by a plain number and negating.
+macro_rules! scalar_unit {
+    ($unit:ident) => {
+        impl $unit {
+            #[inline(always)]
+            pub fn value(&self) -> f32 {
+                self.0
+            }
+
+            pub fn abs(self) -> Self {
+                $unit(self.0.abs())
+            }
+
+            pub fn min(self, other: Self) -> Self {
+                $unit(self.0.min(other.0))
+            }
+
+            pub fn max(self, other: Self) -> Self {
+                $unit(self.0.max(other.0))
+            }
+
+            pub fn clamp(self, min: Self, max: Self) -> Self {
+                self.max(min).min(max)
+            }
+        }
+
+        impl Add for $unit {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $unit(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $unit {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $unit(self.0 - other.0)
+            }
+        }
+
+        impl AddAssign for $unit {
+            fn add_assign(&mut self, other: Self) {
+                self.0 += other.0;
+            }
+        }
+
+        impl SubAssign for $unit {
+            fn sub_assign(&mut self, other: Self) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl Mul<f32> for $unit {
+            type Output = Self;
+
+            fn mul(self, scale: f32) -> Self {
+                $unit(self.0 * scale)
+            }
+        }
+
+        impl Div<f32> for $unit {
+            type Output = Self;
+
+            fn div(self, scale: f32) -> Self {
+                $unit(self.0 / scale)
+            }
+        }
+
+        /// The ratio between two quantities of the same unit.
+        impl Div for $unit {
+            type Output = f32;
+
+            fn div(self, other: Self) -> f32 {
+                self.0 / other.0
+            }
+        }
+
+        impl Neg for $unit {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $unit(-self.0)
+            }
+        }
+    };
+}
+
+scalar_unit!(Pixels);
+scalar_unit!(PixelsPerMs);
+scalar_unit!(PixelsPerMsSquared);
+scalar_unit!(Radians);
+scalar_unit!(Degrees);
+scalar_unit!(RadiansPerMs);
+
+/// A duration as a fractional number of milliseconds.
+pub fn milliseconds(time: Duration) -> f32 {
+    time.num_microseconds().unwrap_or(0) as f32 / 1000.0
+}
+
+impl Mul<Duration> for PixelsPerMs {
+    type Output = Pixels;
+
+    fn mul(self, time: Duration) -> Pixels {
+        Pixels(self.0 * milliseconds(time))
+    }
+}
+
+impl Div<Duration> for Pixels {
+    type Output = PixelsPerMs;
+
+    fn div(self, time: Duration) -> PixelsPerMs {
+        PixelsPerMs(self.0 / milliseconds(time))
+    }
+}
+
+impl Mul<Duration> for PixelsPerMsSquared {
+    type Output = PixelsPerMs;
+
+    fn mul(self, time: Duration) -> PixelsPerMs {
+        PixelsPerMs(self.0 * milliseconds(time))
+    }
+}
+
+impl Div<Duration> for PixelsPerMs {
+    type Output = PixelsPerMsSquared;
+
+    fn div(self, time: Duration) -> PixelsPerMsSquared {
+        PixelsPerMsSquared(self.0 / milliseconds(time))
+    }
+}
+
+impl Mul<Duration> for RadiansPerMs {
+    type Output = Radians;
+
+    fn mul(self, time: Duration) -> Radians {
+        Radians(self.0 * milliseconds(time))
+    }
+}
+
+impl Div<Duration> for Radians {
+    type Output = RadiansPerMs;
+
+    fn div(self, time: Duration) -> RadiansPerMs {
+        RadiansPerMs(self.0 / milliseconds(time))
+    }
+}
+
+impl Radians {
+    pub fn sin_cos(self) -> (f32, f32) {
+        self.0.sin_cos()
+    }
+}
+
+impl From<Degrees> for Radians {
+    fn from(degrees: Degrees) -> Self {
+        Radians(degrees.0.to_radians())
+    }
+}
 
-impl Pixels {
-    #[inline(always)]
-    pub fn value(&self) -> f32 {
-        self.0
+impl From<Radians> for Degrees {
+    fn from(radians: Radians) -> Self {
+        Degrees(radians.0.to_degrees())
     }
 }
@@ -0,0 +1,128 @@
+use glhf::{
+    config::ui::UI,
+    game::{
+        archetypes::Registry,
+        player,
+        simulation::Simulation,
+        stage::{Director, Stage, StageError},
+        world::World,
+    },
+    physics::{
+        collision::Rect,
+        motion::Position,
+        units::Pixels,
+    },
+};
+
+
+#[test]
+fn the_bundled_archetypes_are_available_by_name() {
+    let registry = Registry::bundled();
+
+    assert_eq!(registry.names(), vec!["gunship", "popcorn", "turret", "warden"]);
+}
+
+#[test]
+fn archetypes_set_an_enemys_stats() {
+    let gunship = Registry::bundled()
+        .get("gunship")
+        .unwrap()
+        .spawn(Position::new(Pixels(100.0), Pixels(50.0)));
+
+    assert_eq!(gunship.score(), 3000);
+    assert_eq!(gunship.drops().len(), 3);
+    assert_eq!(gunship.muzzle_position(), Position::new(Pixels(132.0), Pixels(78.0)));
+
+    let mut world = World::new();
+    let gunship = world.spawn(gunship);
+    assert_eq!(world.health(gunship).unwrap().maximum(), 400);
+    assert_eq!(world.hitbox(gunship).unwrap().bounds(), Rect::new(116.0, 58.0, 32.0, 40.0));
+}
+
+#[test]
+fn stages_can_define_and_customise_archetypes() {
+    let stage = Stage::parse(r#"
+        Stage(
+            name: "Custom",
+            archetypes: {
+                "mine": Archetype(size: (16, 16), health: 1),
+            },
+            waves: [
+                Wave(at: 0, enemies: [
+                    Spawn(archetype: "mine", position: (0, 0)),
+                    Spawn(archetype: "turret", position: (100, 0), emitters: []),
+                ]),
+            ],
+        )
+    "#).unwrap();
+
+    let mut director = Director::new(stage);
+    let enemies = director.spawn_due();
+    assert_eq!(enemies.len(), 2);
+
+    // The turret would normally fire straight away.
+    assert!(enemies[1].armament.emitters.is_empty());
+
+    let mut world = World::new();
+    let mine = world.spawn(enemies[0].clone());
+    assert_eq!(world.health(mine).unwrap().maximum(), 1);
+}
+
+#[test]
+fn unknown_archetypes_are_rejected() {
+    let result = Stage::parse(r#"
+        Stage(name: "Typo", waves: [
+            Wave(at: 0, enemies: []),
+            Wave(at: 100, enemies: [Spawn(archetype: "popcon", position: (0, 0))]),
+        ])
+    "#);
+
+    match result {
+        Err(StageError::UnknownArchetype { wave, name }) => {
+            assert_eq!(wave, 1);
+            assert_eq!(name, "popcon");
+        },
+        other => panic!("expected an unknown archetype, got {:?}", other),
+    }
+}
+
+#[test]
+fn shooting_an_enemy_down_scores_its_value() {
+    let stage = Stage::parse(r#"
+        Stage(name: "Target practice", waves: [
+            Wave(at: 0, enemies: [Spawn(archetype: "popcorn", position: (288, 300), emitters: [])]),
+        ])
+    "#).unwrap();
+    let mut simulation = Simulation::with_stage(UI::default(), 0, stage);
+
+    simulation.tick(&[player::Action::StartFiring]);
+    for _ in 1..60 {
+        simulation.tick(&[]);
+    }
+
+    assert!(simulation.world().enemies().next().is_none());
+    assert_eq!(simulation.score(), 100);
+    assert!(simulation.stage_cleared());
+}
+
+#[test]
+fn archetypes_without_health_are_rejected_unless_they_are_bosses() {
+    let result = Registry::parse(r#"{
+        "ghost": Archetype(size: (16, 16), score: 100),
+    }"#);
+
+    match result {
+        Err(StageError::NoHealth { archetype }) => assert_eq!(archetype, "ghost"),
+        other => panic!("expected an archetype with no health, got {:?}", other),
+    }
+
+    let stage = Stage::parse(r#"
+        Stage(name: "Haunted", archetypes: {"ghost": Archetype(size: (16, 16))}, waves: [])
+    "#);
+    assert!(matches!(stage, Err(StageError::NoHealth { .. })));
+
+    let boss = Registry::parse(r#"{
+        "wraith": Archetype(size: (64, 64), phases: [Phase(name: "only", health: 100)]),
+    }"#);
+    assert!(boss.is_ok());
+}
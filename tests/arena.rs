@@ -0,0 +1,108 @@
+mod common;
+
+use glhf::{
+    game::{
+        arena::Arena,
+        enemies::Enemy,
+        health::HealthPoints,
+    },
+    physics::{
+        motion::{Dimensions, Position},
+        units::Pixels,
+    },
+};
+
+use common::empty_stage;
+
+
+#[test]
+fn handles_find_what_they_were_given_for() {
+    let mut arena = Arena::new();
+    let first = arena.insert("first");
+    let second = arena.insert("second");
+
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.get(first), Some(&"first"));
+    assert_eq!(arena.get(second), Some(&"second"));
+    assert_eq!(arena.values().copied().collect::<Vec<_>>(), vec!["first", "second"]);
+}
+
+#[test]
+fn handles_go_stale_once_their_value_is_removed() {
+    let mut arena = Arena::new();
+    let first = arena.insert(1);
+    arena.insert(2);
+
+    assert_eq!(arena.remove(first), Some(1));
+    assert_eq!(arena.remove(first), None);
+
+    // The freed slot is reused, but the old handle doesn't see the new value.
+    let third = arena.insert(3);
+    assert_eq!(third.index(), first.index());
+    assert!(!arena.contains(first));
+    assert_eq!(arena.get(third), Some(&3));
+    assert_eq!(arena.values().copied().collect::<Vec<_>>(), vec![3, 2]);
+}
+
+#[test]
+fn retain_removes_in_one_pass() {
+    let mut arena: Arena<u32> = (0..10).fold(Arena::new(), |mut arena, value| {
+        arena.insert(value);
+        arena
+    });
+    let handles: Vec<_> = arena.iter().map(|(handle, _)| handle).collect();
+
+    arena.retain(|_, value| *value % 3 == 0);
+
+    assert_eq!(arena.len(), 4);
+    assert_eq!(arena.values().copied().collect::<Vec<_>>(), vec![0, 3, 6, 9]);
+    assert!(arena.contains(handles[3]));
+    assert!(!arena.contains(handles[4]));
+    assert_eq!(arena.handle_at(3), Some(handles[3]));
+    assert_eq!(arena.handle_at(4), None);
+}
+
+#[test]
+fn a_pool_reuses_its_slots_instead_of_growing() {
+    let mut arena = Arena::with_capacity(8);
+    let capacity = arena.capacity();
+
+    for round in 0..1000 {
+        let handles: Vec<_> = (0..8).map(|value| arena.insert(round * 8 + value)).collect();
+
+        for handle in handles.into_iter().rev() {
+            arena.remove(handle);
+        }
+    }
+
+    assert!(arena.is_empty());
+    assert_eq!(arena.capacity(), capacity);
+}
+
+#[test]
+fn simulation_handles_last_across_ticks() {
+    let mut simulation = empty_stage();
+
+    let enemy = |x: f32, health: u32| Enemy::new(
+        Position::new(Pixels(x), Pixels(100.0)),
+        Dimensions::new(Pixels(32.0), Pixels(32.0)),
+        HealthPoints::new(health),
+        vec![],
+    );
+
+    let doomed = simulation.add_enemy(enemy(100.0, 0));
+    let survivor = simulation.add_enemy(enemy(300.0, 50));
+
+    for _ in 0..10 {
+        simulation.tick(&[]);
+    }
+
+    assert!(!simulation.world().contains(doomed));
+    assert_eq!(simulation.world().transforms.get(survivor).unwrap().position.x, Pixels(300.0));
+
+    // Whatever takes the shot down enemy's place doesn't answer to its handle.
+    let replacement = simulation.add_enemy(enemy(200.0, 50));
+    assert_eq!(replacement.index(), doomed.index());
+    assert!(!simulation.world().contains(doomed));
+    assert!(simulation.world().health(doomed).is_none());
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use glhf::{
+    config::ui::UI,
+    game::{
+        bombs::{self, Bombs},
+        boss::Phase,
+        bullets::{Bullet, Kind},
+        components::Faction,
+        enemies::Enemy,
+        health::HealthPoints,
+        lives::{self, Lives},
+        player::Action,
+        replay::{Header, Replay},
+        simulation::Simulation,
+    },
+    physics::{
+        motion::{Dimensions, Position},
+        units::Pixels,
+    },
+};
+
+use chrono::Duration;
+
+use common::{empty_stage, lives, shoot_player, wait};
+
+
+fn bombs(simulation: &Simulation) -> &Bombs {
+    simulation.world().bombs.get(simulation.player()).unwrap()
+}
+
+fn enemy_bullets(simulation: &Simulation) -> usize {
+    let world = simulation.world();
+
+    world.bullets().filter(|&bullet| world.factions.get(bullet) == Some(&Faction::Enemy)).count()
+}
+
+/// Shoot the player until they take a fatal hit.
+fn fatally_hit(simulation: &mut Simulation) {
+    while !lives(simulation).is_hit() {
+        shoot_player(simulation);
+        simulation.tick(&[]);
+    }
+}
+
+#[test]
+fn bombs_clear_bullets_and_hurt_every_enemy() {
+    let mut simulation = empty_stage();
+    let sturdy = simulation.add_enemy(Enemy::new(
+        Position::new(Pixels(100.0), Pixels(100.0)),
+        Dimensions::new(Pixels(32.0), Pixels(32.0)),
+        HealthPoints::new(bombs::DAMAGE + 50),
+        vec![],
+    ));
+    let flimsy = simulation.add_enemy(Enemy::new(
+        Position::new(Pixels(300.0), Pixels(100.0)),
+        Dimensions::new(Pixels(32.0), Pixels(32.0)),
+        HealthPoints::new(bombs::DAMAGE),
+        vec![],
+    ));
+    for x in 0..5 {
+        simulation.add_bullet(Bullet::new(Faction::Enemy, Kind::Basic, Position::new(Pixels(x as f32 * 50.0), Pixels(200.0))));
+    }
+    let stock = bombs(&simulation).stock();
+
+    simulation.tick(&[Action::Bomb]);
+
+    assert_eq!(enemy_bullets(&simulation), 0);
+    assert_eq!(simulation.world().health(sturdy).unwrap().current(), 50);
+    assert!(!simulation.world().contains(flimsy), "shot down by the bomb");
+    assert_eq!(bombs(&simulation).stock(), stock - 1);
+    assert!(lives(&simulation).is_alive() && !lives(&simulation).is_vulnerable());
+}
+
+#[test]
+fn there_is_no_bombing_without_bombs() {
+    let mut simulation = empty_stage();
+    for _ in 0..bombs(&simulation).stock() {
+        simulation.tick(&[Action::Bomb]);
+        wait(&mut simulation, bombs::INVULNERABILITY);
+    }
+    simulation.add_bullet(Bullet::new(Faction::Enemy, Kind::Basic, Position::new(Pixels(0.0), Pixels(0.0))));
+
+    simulation.tick(&[Action::Bomb]);
+
+    assert_eq!(bombs(&simulation).stock(), 0);
+    assert_eq!(enemy_bullets(&simulation), 1);
+}
+
+#[test]
+fn only_one_bomb_goes_off_at_a_time() {
+    let mut simulation = empty_stage();
+    let stock = bombs(&simulation).stock();
+
+    simulation.tick(&[Action::Bomb]);
+    simulation.tick(&[Action::Bomb]);
+    assert_eq!(bombs(&simulation).stock(), stock - 1);
+
+    wait(&mut simulation, bombs::INVULNERABILITY);
+    simulation.tick(&[Action::Bomb]);
+    assert_eq!(bombs(&simulation).stock(), stock - 2, "once the first is over");
+}
+
+#[test]
+fn bombs_wear_bosses_down_without_ending_a_phase() {
+    let phase = |name: &str| Phase {
+        name: name.to_string(),
+        health: bombs::BOSS_DAMAGE + 10,
+        duration: None,
+        emitters: vec![],
+        script: None,
+        path: vec![],
+    };
+    let mut simulation = empty_stage();
+    let boss = simulation.add_enemy(Enemy::new(
+        Position::new(Pixels(200.0), Pixels(50.0)),
+        Dimensions::new(Pixels(64.0), Pixels(64.0)),
+        HealthPoints::new(1),
+        vec![],
+    ).with_phases(vec![phase("first"), phase("second")]));
+
+    simulation.tick(&[Action::Bomb]);
+    assert_eq!(simulation.world().health(boss).unwrap().current(), 10);
+
+    wait(&mut simulation, bombs::INVULNERABILITY);
+    simulation.tick(&[Action::Bomb]);
+    assert_eq!(bombs(&simulation).stock(), 1);
+    assert_eq!(simulation.world().health(boss).unwrap().current(), 1, "the phase hangs on");
+    assert_eq!(simulation.world().bosses.get(boss).unwrap().current().name, "first");
+}
+
+#[test]
+fn deathbombing_cancels_the_death() {
+    let mut simulation = empty_stage();
+    let remaining = lives(&simulation).remaining();
+
+    fatally_hit(&mut simulation);
+    simulation.tick(&[Action::Bomb]);
+
+    assert!(lives(&simulation).is_alive() && !lives(&simulation).is_hit());
+    assert_eq!(lives(&simulation).remaining(), remaining);
+    assert!(simulation.world().health(simulation.player()).unwrap().full());
+
+    // Too late, and the bomb is kept.
+    let mut simulation = empty_stage();
+    let stock = bombs(&simulation).stock();
+
+    fatally_hit(&mut simulation);
+    let window = lives::DEATHBOMB_WINDOW.num_microseconds().unwrap() / simulation.tick_length().num_microseconds().unwrap();
+    for _ in 0..=window {
+        simulation.tick(&[]);
+    }
+    simulation.tick(&[Action::Bomb]);
+
+    assert!(!lives(&simulation).is_alive());
+    assert_eq!(lives(&simulation).remaining(), remaining - 1);
+    assert_eq!(bombs(&simulation).stock(), stock);
+}
+
+#[test]
+fn bombing_shields_the_player_for_a_while() {
+    let mut lives = Lives::new(1, Position::new(Pixels(0.0), Pixels(0.0)));
+
+    lives.shield(bombs::INVULNERABILITY);
+    assert!(!lives.is_vulnerable());
+
+    lives.update(bombs::INVULNERABILITY - Duration::milliseconds(1));
+    assert!(!lives.is_vulnerable());
+    lives.update(Duration::milliseconds(1));
+    assert!(lives.is_vulnerable());
+}
+
+#[test]
+fn bombs_are_replenished_up_to_the_maximum() {
+    let mut bombs = Bombs::new(1, 3);
+
+    assert_eq!(bombs.replenish(1), 2);
+    assert_eq!(bombs.replenish(5), 3);
+    assert!(bombs.take());
+    assert_eq!(bombs.stock(), 2);
+}
+
+#[test]
+fn bombs_are_recorded_in_replays() {
+    let mut replay = Replay::new(Header::new(0, &UI::default()));
+    replay.record(4, &[Action::Bomb]);
+
+    let parsed = Replay::parse(&replay.to_string()).unwrap();
+    assert_eq!(parsed.actions_at(4), &[Action::Bomb]);
+}
@@ -0,0 +1,115 @@
+use glhf::{
+    config::ui::UI,
+    game::{
+        boss::Phase,
+        components::Faction,
+        enemies::Enemy,
+        health::{Health, HealthPoints},
+        simulation::Simulation,
+        stage::Stage,
+        systems,
+        world::World,
+    },
+    physics::{
+        motion::{Dimensions, Position},
+        units::Pixels,
+    },
+};
+
+use chrono::Duration;
+
+
+fn phase(name: &str, health: u32, duration: Option<i64>) -> Phase {
+    Phase {
+        name: name.to_string(),
+        health,
+        duration: duration.map(Duration::milliseconds),
+        emitters: vec![],
+        script: None,
+        path: vec![],
+    }
+}
+
+fn boss(phases: Vec<Phase>) -> Enemy {
+    Enemy::new(
+        Position::new(Pixels(200.0), Pixels(50.0)),
+        Dimensions::new(Pixels(64.0), Pixels(64.0)),
+        HealthPoints::new(1),
+        vec![],
+    ).with_phases(phases)
+}
+
+#[test]
+fn each_phase_has_its_own_health_bar() {
+    let mut world = World::new();
+    let boss = world.spawn(boss(vec![phase("first", 100, None), phase("second", 250, None)]));
+    assert_eq!(world.health(boss).unwrap().maximum(), 100);
+
+    world.healths.get_mut(boss).unwrap().take_damage(HealthPoints::new(60));
+    assert!(!systems::boss_phases(&mut world, Duration::milliseconds(16)));
+
+    world.healths.get_mut(boss).unwrap().take_damage(HealthPoints::new(40));
+    assert!(systems::boss_phases(&mut world, Duration::milliseconds(16)));
+    assert_eq!(world.bosses.get(boss).unwrap().current().name, "second");
+    assert_eq!(world.bosses.get(boss).unwrap().remaining(), 0);
+    assert_eq!(world.health(boss), Some(HealthPoints::new(250)));
+}
+
+#[test]
+fn running_out_of_time_forces_the_next_phase() {
+    let mut world = World::new();
+    let boss = world.spawn(boss(vec![phase("timed", 100, Some(1000)), phase("last stand", 100, Some(500))]));
+
+    assert!(!systems::boss_phases(&mut world, Duration::milliseconds(999)));
+    assert_eq!(world.bosses.get(boss).unwrap().time_left(), Some(Duration::milliseconds(1)));
+    assert!(systems::boss_phases(&mut world, Duration::milliseconds(1)));
+    assert_eq!(world.bosses.get(boss).unwrap().index(), 1);
+
+    // Timing out of the last phase ends the fight.
+    assert!(systems::boss_phases(&mut world, Duration::milliseconds(500)));
+    assert!(world.health(boss).unwrap().empty());
+}
+
+#[test]
+fn phase_transitions_clear_enemy_bullets() {
+    let stage = Stage::parse(r#"
+        Stage(
+            name: "Boss rush",
+            archetypes: {
+                "sentry": Archetype(size: (64, 64), phases: [
+                    Phase(name: "barrage", health: 50, duration: 1000, emitters: [
+                        Emitter(pattern: Ring(count: 8), speed: 0.1, interval: 100),
+                    ]),
+                    Phase(name: "quiet", health: 50),
+                ]),
+            },
+            waves: [Wave(at: 0, enemies: [Spawn(archetype: "sentry", position: (268, 100))])],
+        )
+    "#).unwrap();
+    let mut simulation = Simulation::with_stage(UI::default(), 0, stage);
+
+    let enemy_bullets = |simulation: &Simulation| {
+        let world = simulation.world();
+
+        world.bullets().filter(|&bullet| world.factions.get(bullet) == Some(&Faction::Enemy)).count()
+    };
+
+    let phase = |simulation: &Simulation| {
+        let world = simulation.world();
+
+        world.bosses.get(world.boss().unwrap()).unwrap().index()
+    };
+
+    // A second's worth of ticks, give or take the rounding of the tick length.
+    simulation.tick(&[]);
+    let mut ticks = 1;
+    while phase(&simulation) == 0 {
+        assert!(enemy_bullets(&simulation) > 0 || ticks < 10);
+
+        simulation.tick(&[]);
+        ticks += 1;
+    }
+
+    assert!((60..=61).contains(&ticks));
+    assert_eq!(enemy_bullets(&simulation), 0);
+}
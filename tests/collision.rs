@@ -0,0 +1,113 @@
+use glhf::{
+    config::ui::UI,
+    game::{
+        archetypes::ShapeSpec,
+        bullets::{Bullet, Kind},
+        components::Faction,
+        player::Player,
+    },
+    physics::{
+        collision::{Rect, Shape},
+        motion::Position,
+        units::{Degrees, Pixels, Radians},
+    },
+};
+
+
+fn circle(x: f32, y: f32, radius: f32) -> Shape {
+    Shape::Circle { centre: (x, y), radius }
+}
+
+fn rect(x: f32, y: f32, w: f32, h: f32) -> Shape {
+    Shape::Rect(Rect::new(x, y, w, h))
+}
+
+fn capsule(from: (f32, f32), to: (f32, f32), radius: f32) -> Shape {
+    Shape::Capsule { from, to, radius }
+}
+
+/// A 20x4 rectangle centred on `(x, y)` and turned by `degrees`.
+fn diamond(x: f32, y: f32, degrees: f32) -> Shape {
+    Shape::RotatedRect { centre: (x, y), half_extents: (10.0, 2.0), angle: Radians::from(Degrees(degrees)) }
+}
+
+/// Check both ways round, since every pair of shapes is tested by one function.
+fn assert_overlap(a: Shape, b: Shape, expected: bool) {
+    assert_eq!(a.overlaps(&b), expected, "{:?} against {:?}", a, b);
+    assert_eq!(b.overlaps(&a), expected, "{:?} against {:?}", b, a);
+}
+
+#[test]
+fn circles_touch_when_closer_than_their_radii() {
+    assert_overlap(circle(0.0, 0.0, 5.0), circle(8.0, 0.0, 3.0), true);
+    assert_overlap(circle(0.0, 0.0, 5.0), circle(6.0, 6.0, 3.0), false);
+}
+
+#[test]
+fn circles_miss_the_corners_of_rectangles() {
+    let square = rect(0.0, 0.0, 10.0, 10.0);
+
+    assert_overlap(circle(12.0, 5.0, 2.0), square, true);
+    assert_overlap(circle(12.0, 12.0, 2.0), square, false);
+    assert_overlap(circle(5.0, 5.0, 1.0), square, true);
+
+    assert_overlap(circle(7.0, -7.0, 2.0), diamond(0.0, 0.0, 45.0), false);
+    assert_overlap(circle(5.0, 5.0, 2.0), diamond(0.0, 0.0, 45.0), true);
+}
+
+#[test]
+fn capsules_cover_the_whole_of_their_length() {
+    let laser = capsule((0.0, 0.0), (0.0, 100.0), 4.0);
+
+    assert_overlap(laser, circle(6.0, 50.0, 3.0), true);
+    assert_overlap(laser, circle(0.0, 108.0, 3.0), false);
+    assert_overlap(laser, capsule((-10.0, 50.0), (10.0, 50.0), 1.0), true);
+    assert_overlap(laser, capsule((10.0, 0.0), (10.0, 100.0), 1.0), false);
+    assert_overlap(laser, capsule((10.0, 0.0), (10.0, 100.0), 6.0), true);
+}
+
+#[test]
+fn capsules_crossing_rectangles_hit_them() {
+    let wall = rect(-5.0, 40.0, 10.0, 10.0);
+
+    assert_overlap(capsule((0.0, 0.0), (0.0, 100.0), 1.0), wall, true);
+    assert_overlap(capsule((0.0, 44.0), (0.0, 46.0), 1.0), wall, true);
+    assert_overlap(capsule((8.0, 0.0), (8.0, 100.0), 2.0), wall, false);
+    assert_overlap(capsule((8.0, 0.0), (8.0, 100.0), 3.0), wall, true);
+    assert_overlap(capsule((-20.0, 0.0), (20.0, 0.0), 1.0), diamond(0.0, 13.0, 90.0), false);
+    assert_overlap(capsule((-20.0, 0.0), (20.0, 0.0), 1.0), diamond(0.0, 11.0, 90.0), true);
+}
+
+#[test]
+fn rotated_rectangles_only_hit_what_they_cover() {
+    assert_overlap(diamond(0.0, 0.0, 45.0), rect(6.0, 6.0, 10.0, 10.0), true);
+    assert_overlap(diamond(0.0, 0.0, -45.0), rect(6.0, 6.0, 10.0, 10.0), false);
+    assert_overlap(diamond(0.0, 0.0, 0.0), diamond(0.0, 0.0, 90.0), true);
+    assert_overlap(diamond(0.0, 0.0, 90.0), diamond(13.0, 0.0, 90.0), false);
+    assert_overlap(rect(0.0, 0.0, 10.0, 10.0), rect(10.0, 10.0, 5.0, 5.0), true);
+}
+
+#[test]
+fn bounds_enclose_each_shape() {
+    assert_eq!(circle(10.0, 10.0, 5.0).bounds(), Rect::new(5.0, 5.0, 10.0, 10.0));
+    assert_eq!(capsule((10.0, 0.0), (0.0, 20.0), 2.0).bounds(), Rect::new(-2.0, -2.0, 14.0, 24.0));
+
+    let upright = diamond(0.0, 0.0, 90.0).bounds();
+    assert!((upright.w - 4.0).abs() < 1e-4 && (upright.h - 20.0).abs() < 1e-4);
+}
+
+#[test]
+fn hitboxes_follow_their_owners_but_not_their_sprites() {
+    let player = Player::new(&UI::default());
+    let (x, y) = (player.transform.position.x.value(), player.transform.position.y.value());
+
+    let hitbox = player.hitbox.at(player.transform.position);
+    assert_eq!(hitbox, circle(x + 12.0, y + 16.0, 4.0));
+
+    let bullet = Bullet::new(Faction::Enemy, Kind::Basic, Position::new(Pixels(x - 14.0), Pixels(y)));
+    assert!(bullet.transform.rect().overlaps(&player.transform.rect()));
+    assert!(!bullet.hitbox().overlaps(&hitbox), "the sprites overlap but the hitboxes don't");
+
+    let spec = ShapeSpec::RotatedRect { centre: (5.0, 5.0), size: (20.0, 4.0), angle: 45.0 };
+    assert_overlap(spec.shape().translated(-5.0, -5.0), diamond(0.0, 0.0, 45.0), true);
+}
@@ -0,0 +1,60 @@
+//! Helpers shared between the integration tests. Each test binary uses only some
+//! of them.
+#![allow(dead_code)]
+
+use glhf::{
+    config::ui::UI,
+    game::{
+        bullets::{Bullet, Kind},
+        components::Faction,
+        lives::Lives,
+        player::{self, Player},
+        simulation::Simulation,
+        stage::Stage,
+        world::{Entity, World},
+    },
+    physics::{
+        motion::Position,
+        units::Pixels,
+    },
+};
+
+use chrono::Duration;
+
+
+/// A simulation of a stage with no waves, so nothing happens that the test doesn't do.
+pub fn empty_stage() -> Simulation {
+    Simulation::with_stage(UI::default(), 0, Stage::parse(r#"Stage(name: "Empty", waves: [])"#).unwrap())
+}
+
+pub fn lives(simulation: &Simulation) -> &Lives {
+    simulation.world().lives.get(simulation.player()).unwrap()
+}
+
+/// Fire an enemy bullet straight at the middle of the player.
+pub fn shoot_player(simulation: &mut Simulation) {
+    let size = Kind::Basic.dimensions();
+    let centre = simulation.world().transforms.get(simulation.player()).unwrap().centre();
+    let position = Position::new(centre.x - size.width / 2.0, centre.y - size.height / 2.0);
+
+    simulation.add_bullet(Bullet::new(Faction::Enemy, Kind::Basic, position));
+}
+
+/// Tick through `time` of game time.
+pub fn wait(simulation: &mut Simulation, time: Duration) {
+    let ticks = (time.num_microseconds().unwrap() as f64 / simulation.tick_length().num_microseconds().unwrap() as f64).ceil();
+
+    for _ in 0..ticks as usize {
+        simulation.tick(&[]);
+    }
+}
+
+/// A world with nothing in it but a player at `(x, y)` with their weapon at `level`.
+pub fn world_with_player(x: f32, y: f32, level: u32) -> (World, Entity) {
+    let mut world = World::new();
+    let player = world.spawn(Player::new(&UI::default()));
+    world.transforms.get_mut(player).unwrap().teleport(Position::new(Pixels(x), Pixels(y)));
+    world.pilots.get_mut(player).unwrap().power_up(level * player::POWER_PER_LEVEL);
+
+    (world, player)
+}
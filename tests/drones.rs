@@ -0,0 +1,142 @@
+mod common;
+
+use glhf::{
+    game::{
+        bullets::Kind,
+        components::Faction,
+        drones,
+        player::{self, Action},
+        rng::Rng,
+        systems,
+        world::{Entity, World},
+    },
+    physics::{
+        motion::{Offset, Position},
+        units::Pixels,
+    },
+};
+
+use chrono::Duration;
+
+use common::world_with_player;
+
+
+/// Run the drone systems for `ticks` ticks, moving the player right by `step` each tick.
+fn fly(world: &mut World, player: Entity, ticks: usize, step: f32) {
+    for _ in 0..ticks {
+        let transform = world.transforms.get_mut(player).unwrap();
+        let moved = transform.position + Offset::new(Pixels(step), Pixels(0.0));
+        transform.move_to(moved);
+
+        systems::record_trails(world);
+        systems::sync_drones(world, player);
+        systems::fly_drones(world);
+    }
+}
+
+/// The middles of `player`'s drones, in formation order.
+fn drone_centres(world: &World, player: Entity) -> Vec<Position<Pixels>> {
+    let mut drones: Vec<_> = world.drones(player)
+        .map(|drone| (world.satellites.get(drone).unwrap().slot(), world.transforms.get(drone).unwrap().centre()))
+        .collect();
+    drones.sort_by_key(|&(slot, _)| slot);
+
+    drones.into_iter().map(|(_, centre)| centre).collect()
+}
+
+#[test]
+fn a_drone_joins_for_each_weapon_level() {
+    let (mut world, player) = world_with_player(300.0, 600.0, 0);
+
+    for level in 0..=player::MAX_LEVEL {
+        fly(&mut world, player, 1, 0.0);
+        assert_eq!(world.drones(player).count(), level as usize);
+
+        world.pilots.get_mut(player).unwrap().power_up(player::POWER_PER_LEVEL);
+    }
+
+    world.despawn(player);
+    systems::sync_drones(&mut world, player);
+    assert!(world.satellites.iter().next().is_none(), "drones leave with their player");
+}
+
+#[test]
+fn drones_tighten_up_while_focused() {
+    let (mut world, player) = world_with_player(300.0, 600.0, 3);
+    fly(&mut world, player, 30, 0.0);
+
+    let centre = world.transforms.get(player).unwrap().centre();
+    let spread = drone_centres(&world, player);
+    assert_eq!(spread.len(), 3);
+    assert_eq!(spread[1], centre + drones::formation(1, 3, false));
+
+    world.pilots.get_mut(player).unwrap().apply_action(Action::Focus);
+    fly(&mut world, player, 30, 0.0);
+
+    let tight = drone_centres(&world, player);
+    assert_eq!(tight[1], centre + drones::formation(1, 3, true));
+    assert!(tight[2].x - tight[0].x < spread[2].x - spread[0].x);
+    assert!(tight[0].y < centre.y, "tucked in in front");
+}
+
+#[test]
+fn drones_join_a_focused_player_in_the_tight_formation() {
+    let (mut world, player) = world_with_player(300.0, 600.0, 0);
+    world.pilots.get_mut(player).unwrap().apply_action(Action::Focus);
+    fly(&mut world, player, 30, 0.0);
+
+    world.pilots.get_mut(player).unwrap().power_up(player::POWER_PER_LEVEL);
+    systems::sync_drones(&mut world, player);
+
+    let centre = world.transforms.get(player).unwrap().centre();
+    assert_eq!(drone_centres(&world, player), vec![centre + drones::formation(0, 1, true)]);
+}
+
+#[test]
+fn drones_trail_behind_the_players_movement() {
+    let (mut world, player) = world_with_player(100.0, 600.0, 2);
+    fly(&mut world, player, 30, 0.0);
+    let resting = drone_centres(&world, player);
+
+    fly(&mut world, player, 6, 5.0);
+    let moving = drone_centres(&world, player);
+    let lag: Vec<f32> = resting.iter().zip(moving.iter()).map(|(before, after)| (after.x - before.x).value()).collect();
+
+    assert!(lag[0] > 0.0, "the first drone has started to follow");
+    assert!(lag[0] > lag[1], "the second drone lags further behind: {:?}", lag);
+
+    fly(&mut world, player, 30, 0.0);
+    let caught_up = drone_centres(&world, player);
+    assert_eq!(caught_up[1].x - resting[1].x, Pixels(30.0));
+}
+
+#[test]
+fn drones_fire_player_bullets_while_the_trigger_is_held() {
+    let (mut world, player) = world_with_player(300.0, 600.0, 2);
+    let mut rng = Rng::new(0);
+    fly(&mut world, player, 1, 0.0);
+
+    systems::drone_fire(&mut world, Duration::milliseconds(16), &mut rng);
+    assert_eq!(world.bullets().count(), 0);
+
+    world.pilots.get_mut(player).unwrap().apply_action(Action::StartFiring);
+    systems::drone_fire(&mut world, Duration::milliseconds(16), &mut rng);
+
+    let bullets: Vec<_> = world.bullets().collect();
+    assert_eq!(bullets.len(), 2);
+    for bullet in bullets {
+        assert_eq!(world.factions.get(bullet), Some(&Faction::Player));
+        assert_eq!(world.projectiles.get(bullet).unwrap().damage(), Kind::Basic.damage(Faction::Player, 2));
+    }
+}
+
+#[test]
+fn dead_players_drones_hold_their_fire() {
+    let (mut world, player) = world_with_player(300.0, 600.0, 1);
+    fly(&mut world, player, 1, 0.0);
+    world.pilots.get_mut(player).unwrap().apply_action(Action::StartFiring);
+    world.lives.get_mut(player).unwrap().die();
+
+    systems::drone_fire(&mut world, Duration::milliseconds(16), &mut Rng::new(0));
+    assert_eq!(world.bullets().count(), 0);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use glhf::{
+    config::ui::UI,
+    game::{
+        player::{Action, Player},
+        replay::{Header, Replay},
+        rng::Rng,
+    },
+    physics::{
+        motion::{Direction, Position},
+        units::{Degrees, Pixels},
+    },
+};
+
+use common::empty_stage;
+
+
+/// How far right the player gets in `ticks` ticks after `actions`.
+fn distance_moved(actions: &[Action], ticks: usize) -> Pixels {
+    let mut simulation = empty_stage();
+    let start = simulation.world().transforms.get(simulation.player()).unwrap().position.x;
+
+    simulation.tick(actions);
+    for _ in 1..ticks {
+        simulation.tick(&[]);
+    }
+
+    simulation.world().transforms.get(simulation.player()).unwrap().position.x - start
+}
+
+#[test]
+fn focusing_slows_the_player_down() {
+    let normal = distance_moved(&[Action::Move(Direction::Right)], 10);
+    let focused = distance_moved(&[Action::Focus, Action::Move(Direction::Right)], 10);
+
+    assert!(focused.value() > 0.0);
+    assert!((focused.value() / normal.value() - 0.4).abs() < 1e-4);
+
+    let unfocused = distance_moved(&[Action::Focus, Action::Unfocus, Action::Move(Direction::Right)], 10);
+    assert_eq!(unfocused, normal);
+}
+
+#[test]
+fn focused_fire_is_concentrated() {
+    let mut pilot = Player::new(&UI::default()).pilot;
+    let mut rng = Rng::new(0);
+    let origin = Position::new(Pixels(100.0), Pixels(100.0));
+
+    // Unfocused, the streams run in parallel.
+    let volley = pilot.shoot(origin, None, &mut rng);
+    assert_eq!(volley.len(), 3);
+    assert!(volley.iter().all(|bullet| bullet.kinematics().velocity.x.value().abs() < 1e-4));
+
+    pilot.apply_action(Action::Focus);
+    assert!(pilot.is_focused());
+
+    let volley = pilot.shoot(origin, None, &mut rng);
+    assert_eq!(volley.len(), 3);
+    for bullet in volley {
+        let heading = Degrees::from(bullet.kinematics().velocity.angle()).value();
+        assert!((heading + 90.0).abs() <= 3.0 + 1e-3, "{} is off centre", heading);
+    }
+}
+
+#[test]
+fn focus_is_recorded_in_replays() {
+    let mut replay = Replay::new(Header::new(0, &UI::default()));
+    replay.record(3, &[Action::Focus, Action::StartFiring]);
+    replay.record(9, &[Action::Unfocus]);
+
+    let parsed = Replay::parse(&replay.to_string()).unwrap();
+    assert_eq!(parsed.actions_at(3), &[Action::Focus, Action::StartFiring]);
+    assert_eq!(parsed.actions_at(9), &[Action::Unfocus]);
+}
@@ -0,0 +1,104 @@
+//! Plays every stored replay through the headless simulation and compares the
+//! result against its golden trace, tick by tick.
+//!
+//! After an intentional gameplay change, regenerate the golden traces with
+//! `GLHF_BLESS=1 cargo test --test golden_replays` and review the diff.
+
+use glhf::game::{
+    replay::Replay,
+    trace::{
+        Divergence,
+        Entity,
+        Trace,
+    },
+};
+
+use std::{
+    env,
+    path::PathBuf,
+};
+
+
+fn fixture(directory: &str, name: &str, extension: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", directory, &format!("{}.{}", name, extension)]
+        .iter()
+        .collect()
+}
+
+fn check_replay(name: &str) {
+    let replay = Replay::load(fixture("replays", name, "replay"))
+        .unwrap_or_else(|err| panic!("could not load replay `{}`: {}", name, err));
+    let actual = Trace::record(&replay)
+        .unwrap_or_else(|err| panic!("could not load the stage of `{}`: {}", name, err));
+
+    let golden_path = fixture("golden", name, "trace");
+    if env::var_os("GLHF_BLESS").is_some() {
+        actual.save(&golden_path).expect("could not write golden trace");
+        return;
+    }
+
+    let expected = Trace::load(&golden_path)
+        .unwrap_or_else(|err| panic!("could not load golden trace `{}`: {}", name, err));
+
+    if let Some(divergence) = actual.first_divergence(&expected) {
+        panic!("replay `{}` {}", name, divergence);
+    }
+}
+
+#[test]
+fn idle() {
+    check_replay("idle");
+}
+
+#[test]
+fn duel() {
+    check_replay("duel");
+}
+
+#[test]
+fn strafe() {
+    check_replay("strafe");
+}
+
+#[test]
+fn waves() {
+    check_replay("waves");
+}
+
+#[test]
+fn replays_are_deterministic() {
+    let replay = Replay::load(fixture("replays", "duel", "replay")).unwrap();
+
+    assert_eq!(Trace::record(&replay).unwrap(), Trace::record(&replay).unwrap());
+}
+
+#[test]
+fn reports_first_divergent_tick_and_entity() {
+    let replay = Replay::load(fixture("replays", "duel", "replay")).unwrap();
+    let expected = Trace::record(&replay).unwrap();
+
+    let mut actual = expected.clone();
+    actual.ticks[40].player ^= 1;
+    actual.ticks[45].bullets.clear();
+
+    assert_eq!(
+        actual.first_divergence(&expected),
+        Some(Divergence { tick: expected.ticks[40].tick, entity: Entity::Player }),
+    );
+
+    let mut actual = expected.clone();
+    actual.ticks.truncate(100);
+
+    assert_eq!(
+        actual.first_divergence(&expected).map(|divergence| divergence.entity),
+        Some(Entity::TraceLength { expected: expected.ticks.len(), found: 100 }),
+    );
+}
+
+#[test]
+fn golden_traces_round_trip() {
+    let replay = Replay::load(fixture("replays", "strafe", "replay")).unwrap();
+    let trace = Trace::record(&replay).unwrap();
+
+    assert_eq!(Trace::parse(&trace.to_string()).unwrap(), trace);
+}
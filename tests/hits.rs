@@ -0,0 +1,183 @@
+mod common;
+
+use glhf::{
+    config::ui::UI,
+    game::{
+        bullets::{Bullet, HitPolicy, Kind},
+        components::Faction,
+        enemies::Enemy,
+        health::HealthPoints,
+        simulation::Simulation,
+        systems::{self, BulletIndex, Impacts},
+        world::{Entity, World},
+    },
+    physics::{
+        motion::{Dimensions, Position},
+        units::Pixels,
+    },
+};
+
+use common::empty_stage;
+
+
+/// A world to shoot at, with an index to look the player's bullets up in.
+struct Range {
+    world: World,
+    index: BulletIndex,
+}
+
+fn empty_range() -> Range {
+    Range {
+        world: World::new(),
+        index: BulletIndex::new(UI::default().hitbox_rect(), 64.0),
+    }
+}
+
+fn enemy_at(range: &mut Range, x: f32, y: f32) -> Entity {
+    range.world.spawn(Enemy::new(
+        Position::new(Pixels(x), Pixels(y)),
+        Dimensions::new(Pixels(32.0), Pixels(32.0)),
+        HealthPoints::new(100),
+        vec![],
+    ))
+}
+
+/// A player's bullet centred on `(x, y)`.
+fn bullet_at(range: &mut Range, x: f32, y: f32, policy: HitPolicy) -> Entity {
+    range.world.spawn(
+        Bullet::new(Faction::Player, Kind::Basic, Position::new(Pixels(x - 10.0), Pixels(y - 10.0)))
+            .with_hit_policy(policy),
+    )
+}
+
+/// An enemy with its top left corner at `(x, y)` that never moves or shoots.
+fn target_at(simulation: &mut Simulation, x: f32, y: f32) -> Entity {
+    simulation.add_enemy(Enemy::new(
+        Position::new(Pixels(x), Pixels(y)),
+        Dimensions::new(Pixels(32.0), Pixels(32.0)),
+        HealthPoints::new(100),
+        vec![],
+    ))
+}
+
+/// Fire a player's bullet of `kind` up the screen from `(x, y)`, and let it fly
+/// until it's gone.
+fn fire(simulation: &mut Simulation, kind: Kind, x: f32, y: f32) {
+    let bullet = simulation.add_bullet(Bullet::new(Faction::Player, kind, Position::new(Pixels(x), Pixels(y))));
+
+    while simulation.world().contains(bullet) {
+        simulation.tick(&[]);
+    }
+}
+
+fn health(range: &Range, enemy: Entity) -> u32 {
+    range.world.health(enemy).unwrap().current()
+}
+
+/// Run the collision and damage systems, returning the bullets used up.
+fn hit(range: &mut Range) -> Vec<Entity> {
+    let mut impacts = Impacts::default();
+
+    systems::index_bullets(&range.world, &mut range.index);
+    systems::enemy_collisions(&mut range.world, &mut range.index, &mut impacts);
+    systems::damage(&mut range.world, &impacts.hits);
+
+    impacts.spent
+}
+
+#[test]
+fn ordinary_bullets_only_hit_one_enemy() {
+    let mut range = empty_range();
+    let front = enemy_at(&mut range, 100.0, 100.0);
+    let behind = enemy_at(&mut range, 104.0, 104.0);
+    let bullet = bullet_at(&mut range, 120.0, 120.0, HitPolicy::First);
+
+    assert_eq!(hit(&mut range), vec![bullet]);
+    assert_eq!(health(&range, front), 90);
+    assert_eq!(health(&range, behind), 100);
+}
+
+#[test]
+fn piercing_bullets_hit_each_enemy_once() {
+    let mut range = empty_range();
+    let enemies: Vec<_> = (0..3).map(|i| enemy_at(&mut range, 100.0, 100.0 + i as f32)).collect();
+
+    let bullet = bullet_at(&mut range, 116.0, 116.0, HitPolicy::Pierce(4));
+    assert!(hit(&mut range).is_empty());
+    assert!(hit(&mut range).is_empty(), "still inside the same enemies");
+    assert!(enemies.iter().all(|&enemy| health(&range, enemy) == 90));
+
+    let fourth = enemy_at(&mut range, 100.0, 104.0);
+    assert_eq!(hit(&mut range), vec![bullet]);
+    assert_eq!(health(&range, fourth), 90);
+}
+
+#[test]
+fn piercing_bullets_stop_after_enough_enemies() {
+    let mut range = empty_range();
+    let enemies: Vec<_> = (0..3).map(|i| enemy_at(&mut range, 100.0, 100.0 + i as f32)).collect();
+
+    let bullet = bullet_at(&mut range, 116.0, 116.0, HitPolicy::Pierce(2));
+
+    assert_eq!(hit(&mut range), vec![bullet]);
+    assert_eq!(enemies.iter().map(|&enemy| health(&range, enemy)).collect::<Vec<_>>(), vec![90, 90, 100]);
+}
+
+#[test]
+fn area_bullets_hit_everything_nearby() {
+    let mut range = empty_range();
+    let struck = enemy_at(&mut range, 100.0, 100.0);
+    let nearby = enemy_at(&mut range, 150.0, 100.0);
+    let distant = enemy_at(&mut range, 300.0, 100.0);
+
+    let bullet = bullet_at(&mut range, 125.0, 116.0, HitPolicy::Area { radius: 40.0 });
+
+    let bullet_hitbox = range.world.hitbox(bullet).unwrap();
+    assert!(!bullet_hitbox.overlaps(&range.world.hitbox(nearby).unwrap()));
+    assert_eq!(hit(&mut range), vec![bullet]);
+    assert_eq!(health(&range, struck), 90);
+    assert_eq!(health(&range, nearby), 90);
+    assert_eq!(health(&range, distant), 100);
+}
+
+#[test]
+fn spent_bullets_are_removed() {
+    let mut range = empty_range();
+    enemy_at(&mut range, 100.0, 100.0);
+    let spent = bullet_at(&mut range, 116.0, 116.0, HitPolicy::First);
+    let missed = bullet_at(&mut range, 400.0, 400.0, HitPolicy::First);
+
+    let spent_bullets = hit(&mut range);
+    systems::cleanup_spent(&mut range.world, &spent_bullets);
+
+    assert!(!range.world.contains(spent));
+    assert!(range.world.contains(missed));
+}
+
+#[test]
+fn piercing_bullets_fly_through_a_few_enemies() {
+    let mut simulation = empty_stage();
+    let column: Vec<_> = [400.0, 320.0, 240.0, 160.0].iter().map(|&y| target_at(&mut simulation, 100.0, y)).collect();
+
+    fire(&mut simulation, Kind::Piercing, 110.0, 500.0);
+
+    let damage = Kind::Piercing.damage(Faction::Player, 0).current();
+    let healths: Vec<_> = column.iter().map(|&enemy| simulation.world().health(enemy).unwrap().current()).collect();
+    assert_eq!(healths, vec![100 - damage, 100 - damage, 100 - damage, 100]);
+}
+
+#[test]
+fn burst_bullets_catch_enemies_close_to_the_one_they_hit() {
+    let mut simulation = empty_stage();
+    let struck = target_at(&mut simulation, 100.0, 300.0);
+    let beside = target_at(&mut simulation, 140.0, 300.0);
+    let behind = target_at(&mut simulation, 100.0, 150.0);
+
+    fire(&mut simulation, Kind::Burst, 108.0, 500.0);
+
+    let damage = Kind::Burst.damage(Faction::Player, 0).current();
+    let health = |enemy| simulation.world().health(enemy).unwrap().current();
+    assert_eq!(health(struck), 100 - damage);
+    assert_eq!(health(beside), 100 - damage);
+    assert_eq!(health(behind), 100, "the bullet stops at the first enemy");
+}
@@ -0,0 +1,155 @@
+use glhf::{
+    config::ui::UI,
+    game::{
+        archetypes::Registry,
+        bullets::{Bullet, Kind},
+        components::Faction,
+        enemies::Enemy,
+        health::HealthPoints,
+        player::{self, Player},
+        rng::Rng,
+        systems::{self, BulletIndex, Impacts, Role},
+        world::{Entity, World},
+    },
+    physics::{
+        motion::{self, Dimensions, Kinematics, Position},
+        units::{Degrees, Pixels, PixelsPerMs},
+    },
+};
+
+use chrono::Duration;
+
+
+fn at(x: f32, y: f32) -> Position<Pixels> {
+    Position::new(Pixels(x), Pixels(y))
+}
+
+fn enemy_at(world: &mut World, x: f32, y: f32) -> Entity {
+    world.spawn(Enemy::new(at(x, y), Dimensions::new(Pixels(32.0), Pixels(32.0)), HealthPoints::new(1000), vec![]))
+}
+
+/// A homing bullet fired by `faction` from `(x, y)`, heading straight up the screen.
+fn homing(faction: Faction, x: f32, y: f32) -> Bullet {
+    let up = motion::Velocity::from_angle(Degrees(-90.0), PixelsPerMs(1.0));
+
+    Bullet::new(faction, Kind::Homing, at(x, y)).with_kinematics(Kinematics::new(up))
+}
+
+fn heading(world: &World, bullet: Entity) -> f32 {
+    Degrees::from(world.velocities.get(bullet).unwrap().0.velocity.angle()).value()
+}
+
+fn lock(world: &World, bullet: Entity) -> Option<Entity> {
+    world.projectiles.get(bullet).unwrap().homing().unwrap().lock()
+}
+
+#[test]
+fn only_homing_bullets_home() {
+    assert!(Kind::Basic.homing(Faction::Player).is_none());
+    assert!(Kind::Homing.homing(Faction::Player).is_some());
+    assert!(Kind::Homing.homing(Faction::Enemy).unwrap().turn_rate < Kind::Homing.homing(Faction::Player).unwrap().turn_rate);
+}
+
+#[test]
+fn player_bullets_lock_on_to_the_nearest_enemy_and_turn_no_faster_than_they_can() {
+    let mut world = World::new();
+    let _far = enemy_at(&mut world, 10.0, 100.0);
+    let near = enemy_at(&mut world, 500.0, 400.0);
+    let bullet = world.spawn(homing(Faction::Player, 300.0, 500.0));
+    let turn_rate = Kind::Homing.homing(Faction::Player).unwrap().turn_rate;
+
+    systems::home_in(&mut world, Duration::milliseconds(50), None);
+
+    assert_eq!(lock(&world, bullet), Some(near));
+    let turned = heading(&world, bullet) + 90.0;
+    assert!(turned > 0.0, "turned right, towards the enemy");
+    assert!(turned <= Degrees::from(turn_rate * Duration::milliseconds(50)).value() + 1e-3);
+}
+
+#[test]
+fn locked_on_bullets_keep_chasing_their_enemy_while_it_lasts() {
+    let mut world = World::new();
+    let target = enemy_at(&mut world, 10.0, 100.0);
+    let nearer = enemy_at(&mut world, 500.0, 400.0);
+    let bullet = world.spawn(homing(Faction::Player, 300.0, 500.0).locked_on(target));
+
+    systems::home_in(&mut world, Duration::milliseconds(50), None);
+    assert_eq!(lock(&world, bullet), Some(target));
+    assert!(heading(&world, bullet) < -90.0, "turned left, towards its lock");
+
+    world.despawn(target);
+    systems::home_in(&mut world, Duration::milliseconds(50), None);
+    assert_eq!(lock(&world, bullet), Some(nearer));
+}
+
+#[test]
+fn enemy_bullets_track_the_player() {
+    let mut world = World::new();
+    let bullet = world.spawn(homing(Faction::Enemy, 300.0, 300.0));
+
+    systems::home_in(&mut world, Duration::milliseconds(100), Some(at(100.0, 300.0)));
+    assert!(heading(&world, bullet) < -90.0, "turned left, towards the player");
+    assert_eq!(lock(&world, bullet), None);
+}
+
+#[test]
+fn homing_bullets_run_down_their_targets() {
+    let mut world = World::new();
+    let enemy = enemy_at(&mut world, 450.0, 150.0);
+    world.spawn(homing(Faction::Player, 100.0, 700.0));
+    let mut index = BulletIndex::new(UI::default().hitbox_rect(), 64.0);
+    let mut impacts = Impacts::default();
+
+    for _ in 0..100 {
+        systems::home_in(&mut world, Duration::milliseconds(16), None);
+        systems::movement(&mut world, Duration::milliseconds(16), Role::Bullet);
+
+        systems::index_bullets(&world, &mut index);
+        systems::enemy_collisions(&mut world, &mut index, &mut impacts);
+        if !impacts.hits.is_empty() {
+            break;
+        }
+    }
+
+    assert_eq!(impacts.hits.first().map(|hit| hit.target), Some(enemy));
+}
+
+#[test]
+fn homing_bullets_fizzle_out() {
+    let mut world = World::new();
+    let bullet = world.spawn(homing(Faction::Enemy, 300.0, 300.0));
+    let lifetime = Kind::Homing.homing(Faction::Enemy).unwrap().lifetime();
+
+    systems::home_in(&mut world, lifetime - Duration::milliseconds(1), None);
+    systems::cleanup_out_of_bounds(&mut world, &UI::default().hitbox_rect());
+    assert!(world.contains(bullet));
+
+    systems::home_in(&mut world, Duration::milliseconds(1), None);
+    systems::cleanup_out_of_bounds(&mut world, &UI::default().hitbox_rect());
+    assert!(!world.contains(bullet));
+}
+
+#[test]
+fn enemies_can_fire_homing_bullets() {
+    let registry = Registry::parse(r#"{
+        "seeker": Archetype(size: (16, 16), health: 1, emitters: [
+            Emitter(pattern: Spread(count: 1, arc: 0), speed: 0.2, interval: 500, kind: Homing),
+        ]),
+    }"#).unwrap();
+
+    let seeker = registry.get("seeker").unwrap();
+    assert_eq!(seeker.emitters[0].kind, Kind::Homing);
+    assert_eq!(Registry::bundled().get("turret").unwrap().emitters[0].kind, Kind::Basic);
+}
+
+#[test]
+fn powered_up_players_fire_homing_shots_from_the_wings() {
+    let mut pilot = Player::new(&UI::default()).pilot;
+    let mut rng = Rng::new(0);
+    let homing_shots = |volley: Vec<Bullet>| volley.iter().filter(|bullet| bullet.kind() == Kind::Homing).count();
+
+    assert_eq!(homing_shots(pilot.shoot(at(100.0, 100.0), None, &mut rng)), 0);
+
+    pilot.power_up(player::POWER_PER_LEVEL);
+    assert_eq!(homing_shots(pilot.shoot(at(100.0, 100.0), None, &mut rng)), 2);
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use glhf::{
+    config::ui::UI,
+    game::{
+        archetypes::Registry,
+        bullets::Kind,
+        components::Faction,
+        enemies::{Drop, Enemy},
+        health::HealthPoints,
+        items::{self, Collectible, Item},
+        player::{self, Action, Player},
+        rng::Rng,
+        systems::{self, Role},
+        world::{Entity, World},
+    },
+    physics::{
+        motion::{Dimensions, Position},
+        units::Pixels,
+    },
+};
+
+use chrono::Duration;
+
+use common::world_with_player;
+
+
+fn at(x: f32, y: f32) -> Position<Pixels> {
+    Position::new(Pixels(x), Pixels(y))
+}
+
+/// Run the item systems for `ticks` ticks, returning the score collected.
+fn run_items(world: &mut World, player: Entity, ticks: usize) -> u64 {
+    let ui = UI::default();
+    let mut score = 0;
+
+    for _ in 0..ticks {
+        systems::attract_items(world, player, &ui);
+        systems::movement(world, Duration::milliseconds(16), Role::Item);
+        score += systems::collect_items(world, player);
+        systems::cleanup_uncollected(world, &ui.hitbox_rect());
+    }
+
+    score
+}
+
+fn magnetised(world: &World, item: Entity) -> bool {
+    world.pickups.get(item).unwrap().is_magnetised()
+}
+
+#[test]
+fn shot_down_enemies_leave_their_drops_behind() {
+    let mut world = World::new();
+    let enemy = world.spawn(
+        Enemy::new(at(100.0, 100.0), Dimensions::new(Pixels(32.0), Pixels(32.0)), HealthPoints::new(10), vec![])
+            .with_drops(vec![
+                Drop { item: Item::Power, chance: 1.0 },
+                Drop { item: Item::Bomb, chance: 0.0 },
+            ]),
+    );
+    *world.healths.get_mut(enemy).unwrap() = HealthPoints::new(10) - HealthPoints::new(10);
+
+    systems::cleanup_defeated(&mut world, &mut Rng::new(0));
+
+    let dropped: Vec<_> = world.pickups.iter().map(|(_, pickup)| pickup.item()).collect();
+    assert_eq!(dropped, vec![Item::Power]);
+    assert!(world.enemies().next().is_none());
+}
+
+#[test]
+fn items_drift_down_and_off_the_screen() {
+    let (mut world, player) = world_with_player(0.0, 700.0, 0);
+    let item = world.spawn(Collectible::new(Item::Points, at(500.0, 400.0)));
+
+    run_items(&mut world, player, 10);
+    assert!(world.transforms.get(item).unwrap().position.y > Pixels(400.0));
+    assert!(!magnetised(&world, item));
+
+    run_items(&mut world, player, 500);
+    assert!(!world.contains(item), "missed items are cleaned up");
+}
+
+#[test]
+fn the_point_of_collection_draws_in_every_item() {
+    let line = UI::default().height * items::COLLECTION_LINE;
+    let (mut world, player) = world_with_player(300.0, line - 40.0, 0);
+    let far = world.spawn(Collectible::new(Item::Points, at(20.0, 700.0)));
+
+    let score = run_items(&mut world, player, 200);
+
+    assert!(!world.contains(far));
+    assert_eq!(score, items::POINTS);
+}
+
+#[test]
+fn focusing_draws_in_items_within_reach() {
+    let (mut world, player) = world_with_player(300.0, 600.0, 0);
+    let centre = world.transforms.get(player).unwrap().centre();
+    let near = world.spawn(Collectible::new(Item::Points, at(centre.x.value() + items::MAGNET_RADIUS - 10.0, centre.y.value())));
+    let far = world.spawn(Collectible::new(Item::Points, at(centre.x.value() - items::MAGNET_RADIUS - 40.0, centre.y.value() - 200.0)));
+
+    run_items(&mut world, player, 1);
+    assert!(!magnetised(&world, near), "only while focused");
+
+    world.pilots.get_mut(player).unwrap().apply_action(Action::Focus);
+    run_items(&mut world, player, 1);
+    assert!(magnetised(&world, near));
+    assert!(!magnetised(&world, far));
+
+    run_items(&mut world, player, 30);
+    assert!(!world.contains(near));
+    assert!(world.contains(far));
+}
+
+#[test]
+fn items_give_the_player_power_lives_and_bombs() {
+    let (mut world, player) = world_with_player(300.0, 600.0, 0);
+    let centre = world.transforms.get(player).unwrap().centre();
+    let lives = world.lives.get(player).unwrap().remaining();
+    let bombs = world.bombs.get(player).unwrap().stock();
+
+    for item in [Item::Power, Item::Power, Item::Life, Item::Bomb] {
+        world.spawn(Collectible::new(item, centre));
+    }
+    run_items(&mut world, player, 1);
+
+    assert!(world.items().next().is_none());
+    assert_eq!(world.pilots.get(player).unwrap().power(), 2);
+    assert_eq!(world.lives.get(player).unwrap().remaining(), lives + 1);
+    assert_eq!(world.bombs.get(player).unwrap().stock(), bombs + 1);
+}
+
+#[test]
+fn weapon_levels_add_streams_and_damage() {
+    let mut pilot = Player::new(&UI::default()).pilot;
+    let mut rng = Rng::new(0);
+    let origin = at(100.0, 100.0);
+    let base = Kind::Basic.damage(Faction::Player, 0);
+
+    let volley = pilot.shoot(origin, None, &mut rng);
+    assert_eq!(pilot.level(), 0);
+    assert!(volley.iter().all(|bullet| bullet.projectile.damage() == base));
+
+    let mut sizes = vec![volley.len()];
+    for level in 1..=player::MAX_LEVEL {
+        pilot.power_up(player::POWER_PER_LEVEL);
+        assert_eq!(pilot.level(), level);
+
+        let volley = pilot.shoot(origin, None, &mut rng);
+        assert!(volley.iter().all(|bullet| bullet.projectile.damage().current() > bullet.kind().damage(Faction::Player, 0).current()));
+        sizes.push(volley.len());
+    }
+
+    assert!(sizes.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", sizes);
+    assert!(sizes[0] < sizes[player::MAX_LEVEL as usize]);
+
+    pilot.power_up(player::POWER_PER_LEVEL);
+    assert_eq!(pilot.level(), player::MAX_LEVEL, "the level tops out");
+}
+
+#[test]
+fn drops_must_name_a_known_item() {
+    assert!(Registry::parse(r#"{ "pinata": Archetype(size: (8, 8), health: 1, drops: [Drop(item: "power"), Drop(item: "life", chance: 0.5)]) }"#).is_ok());
+    assert!(Registry::parse(r#"{ "pinata": Archetype(size: (8, 8), health: 1, drops: [Drop(item: "gold")]) }"#).is_err());
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use glhf::{
+    game::{
+        components::Faction,
+        lives::{self, Life, Lives},
+        player::Action,
+        simulation::Simulation,
+    },
+    physics::{
+        motion::{Direction, Position},
+        units::Pixels,
+    },
+};
+
+use chrono::Duration;
+
+use common::{empty_stage, lives, shoot_player, wait};
+
+
+fn position(simulation: &Simulation) -> Position<Pixels> {
+    simulation.world().transforms.get(simulation.player()).unwrap().position
+}
+
+/// Shoot the player until they die.
+fn kill(simulation: &mut Simulation) {
+    while lives(simulation).is_alive() {
+        shoot_player(simulation);
+        simulation.tick(&[]);
+    }
+}
+
+#[test]
+fn dying_costs_a_life_and_respawns_the_player_where_they_started() {
+    let mut simulation = empty_stage();
+    let start = position(&simulation);
+
+    simulation.tick(&[Action::Move(Direction::Left)]);
+    wait(&mut simulation, Duration::milliseconds(200));
+    simulation.tick(&[Action::StopMoving(Direction::Left)]);
+    assert_ne!(position(&simulation), start);
+
+    kill(&mut simulation);
+    assert_eq!(lives(&simulation).remaining(), 2);
+    assert!(matches!(lives(&simulation).life(), Life::Dead { .. }));
+    assert!(lives(&simulation).explosion().is_some());
+
+    wait(&mut simulation, lives::RESPAWN_DELAY);
+    assert!(matches!(lives(&simulation).life(), Life::Invulnerable { .. }));
+    assert_eq!(position(&simulation), start);
+    assert!(simulation.world().health(simulation.player()).unwrap().full());
+}
+
+#[test]
+fn the_dead_can_neither_move_nor_shoot() {
+    let mut simulation = empty_stage();
+    kill(&mut simulation);
+    let corpse = position(&simulation);
+
+    simulation.tick(&[Action::Move(Direction::Up), Action::StartFiring]);
+    wait(&mut simulation, Duration::milliseconds(500));
+
+    assert_eq!(position(&simulation), corpse);
+    assert_eq!(simulation.world().bullets().filter(|&bullet| simulation.world().factions.get(bullet) == Some(&Faction::Player)).count(), 0);
+}
+
+#[test]
+fn bullets_pass_through_the_player_while_invulnerable() {
+    let mut simulation = empty_stage();
+    kill(&mut simulation);
+    wait(&mut simulation, lives::RESPAWN_DELAY);
+    assert!(!lives(&simulation).is_vulnerable());
+
+    shoot_player(&mut simulation);
+    simulation.tick(&[]);
+    assert!(simulation.world().health(simulation.player()).unwrap().full());
+    assert_eq!(simulation.world().bullets().count(), 1, "the bullet carries on");
+
+    wait(&mut simulation, lives::INVULNERABILITY);
+    assert!(lives(&simulation).is_vulnerable());
+
+    shoot_player(&mut simulation);
+    simulation.tick(&[]);
+    assert!(!simulation.world().health(simulation.player()).unwrap().full());
+}
+
+#[test]
+fn running_out_of_lives_ends_the_game() {
+    let mut simulation = empty_stage();
+
+    for remaining in (0..3).rev() {
+        assert!(!simulation.game_over());
+
+        kill(&mut simulation);
+        assert_eq!(lives(&simulation).remaining(), remaining);
+        wait(&mut simulation, lives::RESPAWN_DELAY + lives::INVULNERABILITY);
+    }
+
+    assert!(simulation.game_over());
+    assert!(!lives(&simulation).is_visible());
+}
+
+#[test]
+fn invulnerable_players_blink() {
+    let mut lives = Lives::new(2, Position::new(Pixels(0.0), Pixels(0.0)));
+    lives.die();
+    lives.update(lives::RESPAWN_DELAY);
+
+    let visible: Vec<bool> = (0..4)
+        .map(|_| {
+            lives.update(Duration::milliseconds(100));
+            lives.is_visible()
+        })
+        .collect();
+
+    assert_eq!(visible, vec![true, false, true, false]);
+}
@@ -0,0 +1,83 @@
+use glhf::physics::{
+    motion::{
+        Acceleration,
+        Easing,
+        Kinematics,
+        Offset,
+        Position,
+        Velocity,
+    },
+    units::{
+        Degrees,
+        Pixels,
+        PixelsPerMs,
+        PixelsPerMsSquared,
+        Radians,
+        RadiansPerMs,
+    },
+};
+
+use chrono::Duration;
+
+
+fn approx(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+#[test]
+fn constant_acceleration_integrates_exactly() {
+    let mut kinematics = Kinematics::stationary()
+        .with_acceleration(Acceleration::new(PixelsPerMsSquared(0.0), PixelsPerMsSquared(0.01)));
+
+    let offset = kinematics.integrate(Duration::milliseconds(100));
+
+    // d = a * t^2 / 2, v = a * t
+    assert!(approx(offset.y.value(), 50.0));
+    assert!(approx(kinematics.velocity.y.value(), 1.0));
+}
+
+#[test]
+fn angular_velocity_curves_without_changing_speed() {
+    let quarter_turn_per_second = Radians::from(Degrees(90.0)) / Duration::seconds(1);
+    let mut kinematics = Kinematics::new(Velocity::new(PixelsPerMs(1.0), PixelsPerMs(0.0)))
+        .with_angular_velocity(quarter_turn_per_second);
+
+    for _ in 0..100 {
+        kinematics.integrate(Duration::milliseconds(10));
+    }
+
+    assert!(approx(kinematics.velocity.speed().value(), 1.0));
+    assert!(approx(kinematics.velocity.x.value(), 0.0));
+    assert!(approx(kinematics.velocity.y.value(), 1.0));
+    assert!(approx(quarter_turn_per_second.value(), RadiansPerMs(std::f32::consts::FRAC_PI_2 / 1000.0).value()));
+}
+
+#[test]
+fn units_combine_dimensionally() {
+    let speed: PixelsPerMs = Pixels(30.0) / Duration::milliseconds(10);
+    let distance: Pixels = speed * Duration::milliseconds(4);
+
+    assert_eq!(speed, PixelsPerMs(3.0));
+    assert_eq!(distance, Pixels(12.0));
+    assert_eq!(-distance + Pixels(2.0) * 3.0, Pixels(-6.0));
+    assert!(Pixels(1.0) < Pixels(2.0));
+
+    let start = Position::new(Pixels(10.0), Pixels(10.0));
+    let velocity = Velocity::new(PixelsPerMs(1.0), PixelsPerMs(-0.5));
+    let end = start + velocity * Duration::milliseconds(20);
+
+    assert_eq!(end, Position::new(Pixels(30.0), Pixels(0.0)));
+    assert_eq!(end - start, Offset::new(Pixels(20.0), Pixels(-10.0)));
+    assert_eq!((end - start) / Duration::milliseconds(20), velocity);
+}
+
+#[test]
+fn easing_curves_start_and_end_in_place() {
+    for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+        assert_eq!(easing.apply(0.0), 0.0);
+        assert_eq!(easing.apply(1.0), 1.0);
+    }
+
+    assert!(Easing::EaseIn.apply(0.25) < 0.25);
+    assert!(Easing::EaseOut.apply(0.25) > 0.25);
+}
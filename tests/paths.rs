@@ -0,0 +1,102 @@
+use glhf::{
+    config::ui::UI,
+    game::{
+        enemies::Enemy,
+        health::HealthPoints,
+        paths::{Follower, Path},
+        simulation::Simulation,
+    },
+    physics::{
+        motion::{Dimensions, Easing, Offset, Position},
+        units::{Pixels, PixelsPerMs},
+    },
+};
+
+use chrono::Duration;
+
+
+fn at(x: f32, y: f32) -> Position<Pixels> {
+    Position::new(Pixels(x), Pixels(y))
+}
+
+fn assert_near(actual: Position<Pixels>, expected: Position<Pixels>) {
+    let distance = (actual - expected).length().value();
+
+    assert!(distance < 0.01, "expected {:?}, found {:?}", expected, actual);
+}
+
+#[test]
+fn easing_shapes_progress_along_a_line() {
+    let linear = Path::new(at(0.0, 0.0)).line_to(at(100.0, 0.0), Duration::seconds(1), Easing::Linear);
+    let eased = Path::new(at(0.0, 0.0)).line_to(at(100.0, 0.0), Duration::seconds(1), Easing::EaseIn);
+
+    assert_near(Follower::new(linear).advance(Duration::milliseconds(500)), at(50.0, 0.0));
+    assert_near(Follower::new(eased).advance(Duration::milliseconds(500)), at(25.0, 0.0));
+}
+
+#[test]
+fn waypoints_are_visited_at_a_steady_speed() {
+    let path = Path::waypoints(at(0.0, 0.0), &[at(100.0, 0.0), at(100.0, 50.0)], PixelsPerMs(0.5));
+    let mut follower = Follower::new(path);
+
+    assert_near(follower.advance(Duration::milliseconds(200)), at(100.0, 0.0));
+    assert_near(follower.advance(Duration::milliseconds(50)), at(100.0, 25.0));
+    assert!(!follower.finished());
+
+    assert_near(follower.advance(Duration::milliseconds(50)), at(100.0, 50.0));
+    assert!(follower.finished());
+}
+
+#[test]
+fn curves_pass_through_their_ends() {
+    let path = Path::new(at(0.0, 0.0))
+        .bezier_to(at(0.0, 100.0), at(100.0, 100.0), at(100.0, 0.0), Duration::seconds(1), Easing::Linear)
+        .spline_through(vec![at(150.0, 50.0), at(200.0, 0.0)], Duration::seconds(1), Easing::Linear);
+    let mut follower = Follower::new(path);
+
+    // Halfway along the Bézier is halfway between its symmetrical control points, three quarters down.
+    assert_near(follower.advance(Duration::milliseconds(500)), at(50.0, 75.0));
+    assert_near(follower.advance(Duration::milliseconds(500)), at(100.0, 0.0));
+    assert_near(follower.advance(Duration::milliseconds(500)), at(150.0, 50.0));
+    assert_near(follower.advance(Duration::milliseconds(500)), at(200.0, 0.0));
+}
+
+#[test]
+fn sine_strafing_sways_across_the_line_of_travel() {
+    let path = Path::new(at(100.0, 100.0))
+        .sine(Offset::new(Pixels(0.0), Pixels(200.0)), Pixels(30.0), Duration::seconds(1), Duration::seconds(2));
+    let mut follower = Follower::new(path);
+
+    assert_near(follower.advance(Duration::milliseconds(250)), at(70.0, 125.0));
+    assert_near(follower.advance(Duration::milliseconds(500)), at(130.0, 175.0));
+    assert_near(follower.advance(Duration::milliseconds(1250)), at(100.0, 300.0));
+}
+
+#[test]
+fn enemies_despawn_after_leaving_the_screen() {
+    let mut simulation = Simulation::new(UI::default(), 0);
+    let path = Path::enter_hover_exit(
+        at(100.0, -60.0),
+        at(100.0, 100.0),
+        Duration::milliseconds(500),
+        at(-100.0, 100.0),
+        PixelsPerMs(1.0),
+    );
+
+    simulation.add_enemy(Enemy::new(
+        path.start(),
+        Dimensions::new(Pixels(32.0), Pixels(32.0)),
+        HealthPoints::new(10),
+        vec![],
+    ).with_path(path));
+
+    // Flying in from off the top of the screen doesn't count as leaving it.
+    simulation.tick(&[]);
+    assert_eq!(simulation.world().enemies().count(), 2);
+
+    // 160 ms in, 500 ms hovering and 200 ms out.
+    for _ in 0..60 {
+        simulation.tick(&[]);
+    }
+    assert_eq!(simulation.world().enemies().count(), 1);
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use glhf::{
+    game::{
+        bullets::Kind,
+        components::Faction,
+        enemies::Enemy,
+        health::HealthPoints,
+        patterns::{Aim, Emitter, Pattern, Target},
+        rng::Rng,
+    },
+    physics::{
+        motion::{Dimensions, Offset, Position, Velocity},
+        units::{Degrees, Pixels, PixelsPerMs, Radians},
+    },
+};
+
+use chrono::Duration;
+
+use common::empty_stage;
+
+
+fn emitter(pattern: Pattern) -> Emitter {
+    Emitter::new(Faction::Enemy, Kind::Basic, pattern, PixelsPerMs(1.0), Duration::milliseconds(100))
+}
+
+fn origin() -> Position<Pixels> {
+    Position::new(Pixels(100.0), Pixels(100.0))
+}
+
+fn headings(bullets: &[glhf::game::bullets::Bullet]) -> Vec<f32> {
+    bullets.iter()
+        .map(|bullet| Degrees::from(bullet.kinematics().velocity.angle()).value().round().rem_euclid(360.0))
+        .collect()
+}
+
+#[test]
+fn spread_fans_around_the_heading() {
+    let bullets = emitter(Pattern::Spread { count: 3, arc: Degrees(60.0).into() })
+        .fire(origin(), None, &mut Rng::new(0));
+
+    assert_eq!(headings(&bullets), vec![60.0, 90.0, 120.0]);
+}
+
+#[test]
+fn ring_covers_the_full_circle() {
+    let bullets = emitter(Pattern::Ring { count: 4 })
+        .with_heading(Degrees(0.0))
+        .fire(origin(), None, &mut Rng::new(0));
+
+    assert_eq!(headings(&bullets), vec![0.0, 90.0, 180.0, 270.0]);
+}
+
+#[test]
+fn spiral_turns_between_volleys() {
+    let mut spiral = emitter(Pattern::Spiral { arms: 2, step: Degrees(10.0).into() })
+        .with_heading(Degrees(0.0));
+    let mut rng = Rng::new(0);
+
+    assert_eq!(headings(&spiral.fire(origin(), None, &mut rng)), vec![0.0, 180.0]);
+    assert_eq!(headings(&spiral.fire(origin(), None, &mut rng)), vec![10.0, 190.0]);
+}
+
+#[test]
+fn aimed_shots_head_for_the_target() {
+    let target = Position::new(Pixels(200.0), Pixels(200.0));
+    let bullets = emitter(Pattern::Aimed { count: 1, arc: Radians(0.0) })
+        .fire(origin(), Some(Target::stationary(target)), &mut Rng::new(0));
+
+    assert_eq!(headings(&bullets), vec![45.0]);
+}
+
+#[test]
+fn predicted_aim_leads_a_moving_target() {
+    // A target 300 px straight below, crossing to the right at 0.6 px/ms, meets a
+    // 1 px/ms bullet after 375 ms, 225 px to the right of where it started.
+    let target = Target::new(
+        Position::new(Pixels(100.0), Pixels(400.0)),
+        Velocity::new(PixelsPerMs(0.6), PixelsPerMs(0.0)),
+    );
+    let aimed = emitter(Pattern::Aimed { count: 1, arc: Radians(0.0) });
+
+    let current = aimed.clone().fire(origin(), Some(target), &mut Rng::new(0));
+    let predicted = aimed.with_aim(Aim::Predicted).fire(origin(), Some(target), &mut Rng::new(0));
+
+    assert_eq!(headings(&current), vec![90.0]);
+    assert_eq!(headings(&predicted), vec![53.0]);
+}
+
+#[test]
+fn predicted_aim_falls_back_when_the_target_cannot_be_caught() {
+    let target = Target::new(
+        Position::new(Pixels(100.0), Pixels(200.0)),
+        Velocity::new(PixelsPerMs(0.0), PixelsPerMs(2.0)),
+    );
+    let bullets = emitter(Pattern::Aimed { count: 1, arc: Radians(0.0) })
+        .with_aim(Aim::Predicted)
+        .fire(origin(), Some(target), &mut Rng::new(0));
+
+    assert_eq!(headings(&bullets), vec![90.0]);
+}
+
+#[test]
+fn bullets_are_centred_on_where_they_are_fired_from() {
+    let size = Kind::Basic.dimensions();
+    let bullets = emitter(Pattern::Ring { count: 4 }).fire(origin(), None, &mut Rng::new(0));
+
+    for bullet in bullets.iter() {
+        assert_eq!(bullet.position(), Position::new(Pixels(100.0) - size.width / 2.0, Pixels(100.0) - size.height / 2.0));
+    }
+}
+
+#[test]
+fn diagonal_aimed_shots_hit_a_player_standing_still() {
+    let mut simulation = empty_stage();
+    let player = simulation.world().transforms.get(simulation.player()).unwrap().centre();
+
+    // The muzzle is up and to the right of the player, so a shot aimed from the
+    // bullet's corner rather than its middle would pass them by.
+    let muzzle = Offset::new(Pixels(16.0), Pixels(16.0));
+    let position = Position::new(player.x + Pixels(160.0), player.y - Pixels(160.0)) - muzzle;
+    simulation.add_enemy(Enemy::new(
+        position,
+        Dimensions::new(Pixels(32.0), Pixels(32.0)),
+        HealthPoints::new(10),
+        vec![emitter(Pattern::Aimed { count: 1, arc: Radians(0.0) })],
+    ).with_muzzle(muzzle));
+
+    for _ in 0..60 {
+        simulation.tick(&[]);
+    }
+
+    assert!(!simulation.world().health(simulation.player()).unwrap().full());
+}
+
+#[test]
+fn enemies_fire_from_their_muzzle_wherever_they_are() {
+    let mut enemy = Enemy::new(
+        origin(),
+        Dimensions::new(Pixels(32.0), Pixels(44.0)),
+        HealthPoints::new(10),
+        vec![emitter(Pattern::Spread { count: 1, arc: Radians(0.0) })],
+    ).with_muzzle(Offset::new(Pixels(4.0), Pixels(8.0)));
+    let mut rng = Rng::new(0);
+
+    enemy.transform.position = Position::new(Pixels(250.0), Pixels(50.0));
+    let volley = enemy.armament.fire(Duration::milliseconds(100), enemy.transform.position, &mut enemy.velocity.0, None, &mut rng);
+
+    assert_eq!(volley.len(), 1);
+    let size = Kind::Basic.dimensions();
+    assert_eq!(volley[0].position(), Position::new(Pixels(254.0) - size.width / 2.0, Pixels(58.0) - size.height / 2.0));
+}
+
+#[test]
+fn scatter_is_reproducible_from_the_seed() {
+    let scatter = emitter(Pattern::Scatter {
+        count: 8,
+        arc: Degrees(90.0).into(),
+        speed_variance: PixelsPerMs(0.25),
+    });
+
+    let first = scatter.clone().fire(origin(), None, &mut Rng::new(7));
+    let second = scatter.clone().fire(origin(), None, &mut Rng::new(7));
+
+    assert_eq!(headings(&first), headings(&second));
+    for bullet in first.iter() {
+        let heading = Degrees::from(bullet.kinematics().velocity.angle()).value();
+        let speed = bullet.kinematics().velocity.speed().value();
+
+        assert!((45.0..=135.0).contains(&heading));
+        assert!((0.75..=1.25).contains(&speed));
+    }
+}
+
+#[test]
+fn automatic_fire_waits_for_the_interval() {
+    let mut emitter = emitter(Pattern::Ring { count: 2 });
+    let mut rng = Rng::new(0);
+    let tick = Duration::milliseconds(40);
+
+    assert!(emitter.update(tick, origin(), None, &mut rng).is_empty());
+    assert!(emitter.update(tick, origin(), None, &mut rng).is_empty());
+    assert_eq!(emitter.update(tick, origin(), None, &mut rng).len(), 2);
+}
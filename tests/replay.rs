@@ -0,0 +1,67 @@
+use glhf::{
+    config::ui::UI,
+    game::replay::{self, Header, Replay, ReplayError},
+};
+
+use std::{env, fs, path::PathBuf, process};
+
+
+/// A stage file of our own to change under a replay, in the temporary directory.
+fn scratch_stage(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("glhf-{}-{}.ron", process::id(), name));
+    fs::write(&path, contents).unwrap();
+
+    path
+}
+
+const EMPTY: &str = r#"Stage(name: "Empty", waves: [])"#;
+
+#[test]
+fn replays_from_older_formats_are_rejected() {
+    let old = "glhf-replay 1\ngame-version 0.1.0\nseed 1\nui 600 800\nlength 10\ntick 0 shoot\n";
+
+    match Replay::parse(old) {
+        Err(ReplayError::Mismatch { field, expected, found }) => {
+            assert_eq!(field, "format version");
+            assert_eq!(expected, replay::FORMAT_VERSION.to_string());
+            assert_eq!(found, "1");
+        },
+        other => panic!("expected a format version mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn the_stage_hash_survives_a_round_trip() {
+    let path = scratch_stage("round-trip", EMPTY);
+    let header = Header::new(5, &UI::default()).with_stage(path.to_str().unwrap()).unwrap();
+    let replay = Replay::new(header);
+
+    let parsed = Replay::parse(&replay.to_string()).unwrap();
+    assert!(parsed.header.stage_hash.is_some());
+    assert_eq!(parsed, replay);
+    assert_eq!(parsed.header.load_stage().unwrap().name, "Empty");
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn replays_of_a_stage_that_has_since_changed_are_rejected() {
+    let path = scratch_stage("changed", EMPTY);
+    let header = Header::new(5, &UI::default()).with_stage(path.to_str().unwrap()).unwrap();
+
+    fs::write(&path, r#"Stage(name: "Busier", waves: [])"#).unwrap();
+
+    match header.load_stage() {
+        Err(ReplayError::Mismatch { field, .. }) => assert_eq!(field, "stage hash"),
+        other => panic!("expected a stage hash mismatch, got {:?}", other.map(|stage| stage.name)),
+    }
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn stage_replays_need_a_stage_hash() {
+    let unhashed = "glhf-replay 2\ngame-version 0.2.0\nseed 1\nui 600 800\nstage stage.ron\nlength 10\n";
+
+    assert!(matches!(Replay::parse(unhashed), Err(ReplayError::Parse { .. })));
+}
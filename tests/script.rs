@@ -0,0 +1,108 @@
+use glhf::{
+    game::{
+        components::Faction,
+        script::{Action, Host, Runner, Script, ScriptError},
+    },
+    physics::{
+        motion::{Kinematics, Position},
+        units::Pixels,
+    },
+};
+
+use std::path::PathBuf;
+
+
+fn resource(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "resources", "scripts", name].iter().collect()
+}
+
+fn run(runner: &mut Runner, kinematics: &mut Kinematics) -> usize {
+    runner.tick(Host {
+        position: Position::new(Pixels(100.0), Pixels(100.0)),
+        kinematics,
+        faction: Faction::Enemy,
+        target: Some(Position::new(Pixels(100.0), Pixels(300.0))),
+    }).len()
+}
+
+#[test]
+fn bundled_scripts_parse() {
+    for name in ["flower.ron", "stream.ron"] {
+        Script::load(resource(name)).unwrap_or_else(|err| panic!("{}: {}", name, err));
+    }
+}
+
+#[test]
+fn syntax_errors_report_line_and_column() {
+    let source = "[\n    Wait(3),\n    Jump(4),\n]";
+
+    match Script::parse(source) {
+        Err(ScriptError::Syntax { line, column, message }) => {
+            assert_eq!(line, 3);
+            assert!(column > 1, "column {}", column);
+            assert!(message.contains("Jump"), "{}", message);
+        },
+        other => panic!("expected a syntax error, got {:?}", other.map(|script| script.actions().len())),
+    }
+}
+
+#[test]
+fn repeats_and_waits_run_tick_by_tick() {
+    let script = Script::parse("[
+        Repeat(times: 3, actions: [
+            Fire(heading: Absolute(90.0), speed: Absolute(0.5)),
+            Fire(heading: Sequence(10.0), speed: Sequence(0.1)),
+            Wait(2),
+        ]),
+    ]").unwrap();
+
+    let mut runner = Runner::new(&script);
+    let mut kinematics = Kinematics::stationary();
+    let fired: Vec<usize> = (0..10).map(|_| run(&mut runner, &mut kinematics)).collect();
+
+    assert_eq!(fired, vec![2, 0, 0, 2, 0, 0, 2, 0, 0, 0]);
+    assert!(runner.finished());
+}
+
+#[test]
+fn nested_bullet_scripts_change_the_bullet() {
+    let script = Script::parse("[
+        Fire(heading: Aim(0.0), speed: Absolute(1.0), bullet: [
+            ChangeSpeed(speed: Absolute(0.5), over: 5),
+            Wait(10),
+            Vanish,
+        ]),
+    ]").unwrap();
+
+    let mut runner = Runner::new(&script);
+    let mut kinematics = Kinematics::stationary();
+    let mut bullet = runner.tick(Host {
+        position: Position::new(Pixels(100.0), Pixels(100.0)),
+        kinematics: &mut kinematics,
+        faction: Faction::Enemy,
+        target: Some(Position::new(Pixels(100.0), Pixels(300.0))),
+    }).remove(0);
+
+    // Aimed straight down at the target.
+    assert!(bullet.kinematics().velocity.x.value().abs() < 1e-6);
+
+    for _ in 0..6 {
+        bullet.run_script(None);
+    }
+    assert!((bullet.kinematics().velocity.speed().value() - 0.5).abs() < 1e-5);
+    assert!(!bullet.vanished());
+
+    for _ in 0..11 {
+        bullet.run_script(None);
+    }
+    assert!(bullet.vanished());
+}
+
+#[test]
+fn loops_without_waits_cannot_hang() {
+    let script = Script(vec![Action::Loop(Script(vec![].into()))].into());
+    let mut runner = Runner::new(&script);
+
+    assert_eq!(run(&mut runner, &mut Kinematics::stationary()), 0);
+    assert!(!runner.finished());
+}
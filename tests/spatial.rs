@@ -0,0 +1,64 @@
+use glhf::physics::{
+    collision::Rect,
+    spatial::Grid,
+};
+
+
+fn candidates(grid: &Grid, rect: Rect) -> Vec<usize> {
+    let mut found = vec![];
+    grid.query(&rect, &mut found);
+    found
+}
+
+#[test]
+fn queries_only_find_nearby_rects() {
+    let mut grid = Grid::new(Rect::new(0.0, 0.0, 640.0, 640.0), 64.0);
+    grid.rebuild(vec![
+        Rect::new(10.0, 10.0, 8.0, 8.0),
+        Rect::new(500.0, 500.0, 8.0, 8.0),
+        Rect::new(40.0, 20.0, 8.0, 8.0),
+    ]);
+
+    assert_eq!(candidates(&grid, Rect::new(0.0, 0.0, 32.0, 32.0)), vec![0, 2]);
+    assert_eq!(candidates(&grid, Rect::new(480.0, 480.0, 16.0, 16.0)), vec![1]);
+    assert!(candidates(&grid, Rect::new(200.0, 200.0, 16.0, 16.0)).is_empty());
+}
+
+#[test]
+fn rects_spanning_cells_are_found_once_from_each() {
+    let mut grid = Grid::new(Rect::new(0.0, 0.0, 640.0, 640.0), 64.0);
+    grid.insert(7, &Rect::new(60.0, 60.0, 8.0, 8.0));
+
+    assert_eq!(candidates(&grid, Rect::new(0.0, 0.0, 128.0, 128.0)), vec![7]);
+    assert_eq!(candidates(&grid, Rect::new(100.0, 100.0, 4.0, 4.0)), vec![7]);
+    assert!(candidates(&grid, Rect::new(140.0, 140.0, 4.0, 4.0)).is_empty());
+}
+
+#[test]
+fn touching_edges_share_a_cell() {
+    let mut grid = Grid::new(Rect::new(0.0, 0.0, 640.0, 640.0), 64.0);
+    grid.insert(0, &Rect::new(0.0, 0.0, 64.0, 8.0));
+
+    let other = Rect::new(64.0, 0.0, 8.0, 8.0);
+    assert!(other.overlaps(&Rect::new(0.0, 0.0, 64.0, 8.0)));
+    assert_eq!(candidates(&grid, other), vec![0]);
+}
+
+#[test]
+fn clearing_forgets_everything() {
+    let mut grid = Grid::new(Rect::new(0.0, 0.0, 640.0, 640.0), 64.0);
+    grid.insert(0, &Rect::new(0.0, 0.0, 8.0, 8.0));
+    grid.clear();
+
+    assert!(candidates(&grid, Rect::new(0.0, 0.0, 8.0, 8.0)).is_empty());
+}
+
+#[test]
+fn rects_outside_the_grid_are_still_found() {
+    let mut grid = Grid::new(Rect::new(0.0, 0.0, 640.0, 640.0), 64.0);
+    grid.insert(0, &Rect::new(-40.0, 300.0, 8.0, 8.0));
+    grid.insert(1, &Rect::new(700.0, 700.0, 8.0, 8.0));
+
+    assert_eq!(candidates(&grid, Rect::new(-36.0, 296.0, 8.0, 8.0)), vec![0]);
+    assert_eq!(candidates(&grid, Rect::new(690.0, 690.0, 16.0, 16.0)), vec![1]);
+}
@@ -0,0 +1,94 @@
+use glhf::{
+    config::ui::UI,
+    game::{
+        simulation::Simulation,
+        stage::{Director, Stage, StageError},
+    },
+    physics::{
+        motion::Position,
+        units::Pixels,
+    },
+};
+
+use std::path::PathBuf;
+
+use chrono::Duration;
+
+
+fn resource(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "resources", "stages", name].iter().collect()
+}
+
+const FLYBY: &str = r#"
+    Stage(
+        name: "Flyby",
+        waves: [
+            Wave(at: 500, enemies: [
+                Spawn(archetype: "popcorn", position: (-30, 100), path: [
+                    Line(to: (-30, 150), duration: 100),
+                ]),
+            ]),
+            Wave(at: 0, enemies: [
+                Spawn(archetype: "popcorn", position: (100, -30), path: [
+                    LineAt(to: (700, -30), speed: 1.0),
+                ]),
+                Spawn(archetype: "turret", position: (300, 20), muzzle: (0, 0)),
+            ]),
+        ],
+    )
+"#;
+
+#[test]
+fn the_bundled_stage_parses() {
+    Stage::load(resource("opening.ron")).unwrap_or_else(|err| panic!("opening.ron: {}", err));
+}
+
+#[test]
+fn syntax_errors_report_line_and_column() {
+    let source = "Stage(name: \"Broken\", waves: [\n    Wave(at: 0, enemies: [\n        Spawn(kind: Dragon, position: (0, 0)),\n    ]),\n])";
+
+    match Stage::parse(source) {
+        Err(StageError::Syntax { line, .. }) => assert_eq!(line, 3),
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+#[test]
+fn waves_arrive_on_schedule() {
+    let mut director = Director::new(Stage::parse(FLYBY).unwrap());
+
+    let first = director.spawn_due();
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[1].transform.dimensions.height, Pixels(44.0));
+    assert_eq!(first[1].muzzle_position(), Position::new(Pixels(300.0), Pixels(20.0)));
+
+    director.advance(Duration::milliseconds(499));
+    assert!(director.spawn_due().is_empty());
+    assert!(!director.finished());
+
+    director.advance(Duration::milliseconds(1));
+    assert_eq!(director.spawn_due().len(), 1);
+    assert!(director.finished());
+}
+
+#[test]
+fn the_stage_is_cleared_once_every_wave_is_gone() {
+    let stage = Stage::parse(FLYBY).unwrap();
+    let mut simulation = Simulation::with_stage(UI::default(), 0, stage);
+
+    // Both drones fly off screen, but the turret stays put.
+    for _ in 0..120 {
+        simulation.tick(&[]);
+        assert!(!simulation.stage_cleared());
+    }
+    assert_eq!(simulation.world().enemies().count(), 1);
+
+    let mut stage = Stage::parse(FLYBY).unwrap();
+    stage.waves[1].enemies.pop();
+    let mut simulation = Simulation::with_stage(UI::default(), 0, stage);
+
+    for _ in 0..120 {
+        simulation.tick(&[]);
+    }
+    assert!(simulation.stage_cleared());
+}
@@ -0,0 +1,97 @@
+use glhf::{
+    config::ui::UI,
+    game::{
+        clock::ManualClock,
+        stage::Stage,
+        state::{Mode, State},
+        timestep::Timestep,
+    },
+};
+
+use chrono::{Duration, TimeZone, Utc};
+
+use ggez::event::KeyCode;
+
+use std::{path::PathBuf, rc::Rc};
+
+
+fn opening() -> Stage {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "resources", "stages", "opening.ron"].iter().collect();
+
+    Stage::load(path).unwrap()
+}
+
+/// A live game whose clock only moves when the test says so.
+fn game() -> (State, Rc<ManualClock>) {
+    let clock = Rc::new(ManualClock::new(Utc.timestamp_opt(0, 0).unwrap()));
+    let state = State::with_clock(UI::default(), 7, opening(), Mode::Live, Box::new(clock.clone()));
+
+    (state, clock)
+}
+
+/// Play for `time`, drawing a frame every `frame` and updating in between.
+fn play(state: &mut State, clock: &ManualClock, time: Duration, frame: Duration) {
+    let frames = time.num_microseconds().unwrap() / frame.num_microseconds().unwrap();
+
+    for _ in 0..frames {
+        clock.advance(frame);
+        state.advance();
+    }
+}
+
+/// What the game looks like after a second of moving right while firing, then
+/// a second of standing still, at `frame` milliseconds per frame.
+fn session(frame: i64) -> (u64, String) {
+    let (mut state, clock) = game();
+    let frame = Duration::milliseconds(frame);
+
+    state.press(KeyCode::Right, false);
+    state.press(KeyCode::Space, false);
+    play(&mut state, &clock, Duration::seconds(1), frame);
+
+    state.release(KeyCode::Right);
+    play(&mut state, &clock, Duration::seconds(1), frame);
+
+    let simulation = state.simulation();
+    let world = simulation.world();
+    let snapshot = format!(
+        "{:?} {} bullets {} enemies {} points",
+        world.transforms.get(simulation.player()).unwrap().position,
+        world.bullets().count(),
+        world.enemies().count(),
+        simulation.score(),
+    );
+
+    (simulation.current_tick(), snapshot)
+}
+
+#[test]
+fn partial_frames_add_up_to_ticks() {
+    let mut timestep = Timestep::default();
+
+    assert_eq!(timestep.accumulate(Duration::milliseconds(10)), 0);
+    assert!((timestep.alpha() - 0.6).abs() < 1e-3, "{}", timestep.alpha());
+
+    assert_eq!(timestep.accumulate(Duration::milliseconds(10)), 1);
+    assert!((timestep.alpha() - 0.2).abs() < 1e-3, "{}", timestep.alpha());
+
+    assert_eq!(timestep.accumulate(Duration::milliseconds(30)), 2);
+}
+
+#[test]
+fn long_stalls_only_run_a_few_ticks() {
+    let mut timestep = Timestep::default();
+
+    assert_eq!(timestep.accumulate(Duration::seconds(1)), 8);
+    assert_eq!(timestep.alpha(), 0.0, "the rest of the backlog is dropped");
+    assert_eq!(timestep.accumulate(Duration::milliseconds(17)), 1);
+}
+
+#[test]
+fn the_frame_rate_makes_no_difference_to_the_game() {
+    let slow = session(50);
+
+    assert_eq!(slow.0, 120);
+    assert_eq!(session(10), slow);
+    assert_eq!(session(4), slow);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use glhf::{
+    game::{
+        bullets::Kind,
+        components::Faction,
+        patterns::{Emitter, Pattern},
+        player::Action,
+        rng::Rng,
+        simulation::Simulation,
+        weapons::Weapon,
+    },
+    physics::{
+        motion::{Offset, Position},
+        units::{Pixels, PixelsPerMs, Radians},
+    },
+};
+
+use chrono::Duration;
+
+use common::empty_stage;
+
+
+fn single_shot() -> Emitter {
+    Emitter::new(Faction::Player, Kind::Basic, Pattern::Spread { count: 1, arc: Radians(0.0) }, PixelsPerMs(1.0), Duration::zero())
+}
+
+fn origin() -> Position<Pixels> {
+    Position::new(Pixels(100.0), Pixels(100.0))
+}
+
+/// How many volleys `weapon` fires in `steps` updates of `step` milliseconds with the trigger held.
+fn volleys(weapon: &mut Weapon, steps: usize, step: i64) -> Vec<usize> {
+    let mut rng = Rng::new(0);
+
+    (0..steps)
+        .filter(|_| !weapon.update(Duration::milliseconds(step), true, origin(), None, &mut rng).is_empty())
+        .collect()
+}
+
+#[test]
+fn holding_the_trigger_fires_at_the_weapons_rate() {
+    let mut weapon = Weapon::new(Duration::milliseconds(100)).with_stream(single_shot(), Offset::new(Pixels(0.0), Pixels(0.0)));
+
+    assert_eq!(volleys(&mut weapon, 35, 10), vec![0, 10, 20, 30]);
+
+    // Ticks that don't divide the cooldown evenly don't slow the rate of fire down.
+    let mut weapon = Weapon::new(Duration::milliseconds(100)).with_stream(single_shot(), Offset::new(Pixels(0.0), Pixels(0.0)));
+    assert_eq!(volleys(&mut weapon, 60, 17).len(), 11);
+}
+
+#[test]
+fn letting_go_keeps_the_cooldown_but_banks_nothing() {
+    let mut weapon = Weapon::new(Duration::milliseconds(100)).with_stream(single_shot(), Offset::new(Pixels(0.0), Pixels(0.0)));
+    let mut rng = Rng::new(0);
+
+    assert_eq!(weapon.fire(origin(), None, &mut rng).len(), 1);
+    weapon.update(Duration::milliseconds(50), false, origin(), None, &mut rng);
+    assert!(!weapon.ready());
+
+    for _ in 0..10 {
+        weapon.update(Duration::milliseconds(50), false, origin(), None, &mut rng);
+    }
+    assert!(weapon.ready());
+    assert_eq!(volleys(&mut weapon, 15, 10), vec![0, 10]);
+}
+
+#[test]
+fn every_stream_fires_centred_on_its_muzzle() {
+    let mut weapon = Weapon::new(Duration::milliseconds(100))
+        .with_stream(single_shot(), Offset::new(Pixels(-8.0), Pixels(0.0)))
+        .with_stream(single_shot(), Offset::new(Pixels(8.0), Pixels(0.0)));
+    let size = Kind::Basic.dimensions();
+
+    let volley = weapon.fire(origin(), None, &mut Rng::new(0));
+    let centres: Vec<_> = volley.iter().map(|bullet| bullet.position() + Offset::new(size.width / 2.0, size.height / 2.0)).collect();
+
+    assert_eq!(centres, vec![
+        Position::new(Pixels(92.0), Pixels(100.0)),
+        Position::new(Pixels(108.0), Pixels(100.0)),
+    ]);
+}
+
+#[test]
+fn the_player_fires_until_they_let_go() {
+    let mut simulation = empty_stage();
+    let player_bullets = |simulation: &Simulation| {
+        let world = simulation.world();
+
+        world.bullets().filter(|&bullet| world.factions.get(bullet) == Some(&Faction::Player)).count()
+    };
+
+    simulation.tick(&[Action::StartFiring]);
+    assert_eq!(player_bullets(&simulation), 3);
+
+    for _ in 0..12 {
+        simulation.tick(&[]);
+    }
+    assert_eq!(player_bullets(&simulation), 6, "a second volley about 100 ms later");
+
+    simulation.tick(&[Action::StopFiring]);
+    let before = player_bullets(&simulation);
+    for _ in 0..12 {
+        simulation.tick(&[]);
+    }
+    assert!(player_bullets(&simulation) <= before);
+}
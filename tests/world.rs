@@ -0,0 +1,98 @@
+use glhf::{
+    config::ui::UI,
+    game::{
+        bullets::{Bullet, Kind},
+        components::{Faction, Hitbox, Transform, Velocity},
+        enemies::Enemy,
+        health::HealthPoints,
+        player::Player,
+        systems::{self, Role},
+        world::{Components, World},
+    },
+    physics::{
+        motion::{self, Dimensions, Kinematics, Position},
+        units::{Pixels, PixelsPerMs},
+    },
+};
+
+use chrono::Duration;
+
+
+fn at(x: f32, y: f32) -> Position<Pixels> {
+    Position::new(Pixels(x), Pixels(y))
+}
+
+fn enemy(x: f32, y: f32) -> Enemy {
+    Enemy::new(at(x, y), Dimensions::new(Pixels(32.0), Pixels(32.0)), HealthPoints::new(10), vec![])
+}
+
+#[test]
+fn entities_are_whatever_components_they_have() {
+    let mut world = World::new();
+    let player = world.spawn(Player::new(&UI::default()));
+    let enemy = world.spawn(enemy(100.0, 100.0));
+    let bullet = world.spawn(Bullet::new(Faction::Enemy, Kind::Basic, at(50.0, 50.0)));
+
+    assert_eq!(world.len(), 3);
+    assert_eq!(world.enemies().collect::<Vec<_>>(), vec![enemy]);
+    assert_eq!(world.bullets().collect::<Vec<_>>(), vec![bullet]);
+    assert!(world.pilots.contains(player));
+
+    assert_eq!(world.factions.get(player), Some(&Faction::Player));
+    assert_eq!(world.factions.get(enemy), Some(&Faction::Enemy));
+    assert!(world.healths.contains(enemy));
+    assert!(!world.healths.contains(bullet), "bullets have no health");
+    assert_eq!(world.renderables.iter().count(), 3);
+}
+
+#[test]
+fn despawning_removes_every_component() {
+    let mut world = World::new();
+    let doomed = world.spawn(enemy(100.0, 100.0));
+
+    assert!(world.despawn(doomed));
+    assert!(!world.despawn(doomed));
+    assert!(world.is_empty());
+    assert!(world.transforms.get(doomed).is_none());
+    assert!(world.hitbox(doomed).is_none());
+    assert!(world.armaments.iter().next().is_none());
+
+    // The slot is reused, but the stale entity doesn't see the new components.
+    let replacement = world.spawn(enemy(200.0, 100.0));
+    assert_eq!(replacement.index(), doomed.index());
+    assert!(world.health(doomed).is_none());
+    assert_eq!(world.transforms.get(replacement).unwrap().position, at(200.0, 100.0));
+}
+
+#[test]
+fn components_are_stored_by_entity() {
+    let mut world = World::new();
+    let first = world.spawn(enemy(0.0, 0.0));
+    let second = world.spawn(enemy(0.0, 0.0));
+
+    let mut hitboxes = Components::new();
+    hitboxes.insert(second, Hitbox::covering(Dimensions::new(Pixels(4.0), Pixels(4.0))));
+
+    assert!(!hitboxes.contains(first));
+    assert!(hitboxes.contains(second));
+    assert_eq!(hitboxes.remove(first), None);
+    assert!(hitboxes.remove(second).is_some());
+    assert_eq!(hitboxes.iter().count(), 0);
+}
+
+#[test]
+fn movement_only_moves_the_role_it_is_given() {
+    let mut world = World::new();
+    let sliding = Velocity(Kinematics::new(motion::Velocity::new(PixelsPerMs(1.0), PixelsPerMs(0.0))));
+
+    let enemy = world.spawn(enemy(100.0, 100.0));
+    world.velocities.insert(enemy, sliding);
+    let bullet = world.spawn(Bullet::new(Faction::Player, Kind::Basic, at(50.0, 50.0)).with_kinematics(sliding.0));
+
+    systems::movement(&mut world, Duration::milliseconds(10), Role::Enemy);
+
+    let moved: &Transform = world.transforms.get(enemy).unwrap();
+    assert_eq!(moved.position, at(110.0, 100.0));
+    assert_eq!(moved.previous_position, at(100.0, 100.0));
+    assert_eq!(world.transforms.get(bullet).unwrap().position, at(50.0, 50.0));
+}